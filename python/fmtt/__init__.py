@@ -1,3 +1,3 @@
-from fmtt._lowlevel import format
+from fmtt._lowlevel import format, format_with, unwrap
 
-__all__ = ["format"]
+__all__ = ["format", "format_with", "unwrap"]
@@ -0,0 +1,146 @@
+use super::*;
+
+/// Options for breaking paragraphs into lines.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BreakConfig {
+    /// How to measure the width of words against the line width limit.
+    pub width_measure: WidthMeasure,
+    /// Rules for CJK characters that must not start or end a line.
+    pub kinsoku: Kinsoku,
+    /// Algorithm to choose where to break lines.
+    pub engine: BreakEngine,
+    /// Priorities of each kind of split point.
+    pub priorities: SplitPriorities,
+    /// Width lines aim for, preferring split points near it among those of
+    /// the same priority, and word boundaries near it below all priorities;
+    /// lines still use the space up to the line width otherwise.
+    /// Defaults to the line width.
+    pub goal_width: Option<usize>,
+    /// Fraction of the line width below which lines only end at split points
+    /// if no split point further along fits; only with [`BreakEngine::Greedy`].
+    pub min_line_fill: f64,
+    /// Number of columns lines may exceed the width by to end at a split point
+    /// of higher priority than all those within the width;
+    /// only with [`BreakEngine::Greedy`].
+    pub overflow: usize,
+    /// Where to break lines around each class of connection words.
+    pub connection_word_breaks: ConnectionWordBreaks,
+    /// Connection words, abbreviations and punctuation of the text's language.
+    /// Serialized as the [`Language`] name if it is a built-in pack.
+    #[serde(with = "language::preset_or_custom")]
+    pub language: LanguagePack,
+    /// Keep line breaks in the input that end lines that still fit and
+    /// sit at split points; only re-flow the other lines.
+    pub stable: bool,
+    /// Line breaks in a previous version of the text to reproduce.
+    /// Not serialized because it depends on the text.
+    #[serde(skip)]
+    pub reference: Option<ReferenceBreaks>,
+    /// Always break lines after sentence ends.
+    pub sentence_per_line: bool,
+    /// Always break lines after sentence ends, and after sub-sentence ends
+    /// once the line since the last such break is at least this wide.
+    pub clause_per_line: Option<usize>,
+    /// Only break lines that are too long; never join lines.
+    pub split_only: bool,
+    /// Spans of paragraphs never to break lines inside.
+    pub glue: Glue,
+    /// Split words after internal hyphens.
+    pub split_hyphens: bool,
+    /// Split words too long for a line after `/`, `_`, `.` and `-`.
+    pub split_long_words: bool,
+    /// Hyphenate English words with TeX's patterns when no split point fits
+    /// a line; only with [`BreakEngine::Greedy`] and languages with
+    /// [`LanguagePack::english_hyphenation`].
+    pub hyphenate: bool,
+    /// Words to never break lines after, in addition to the built-in ones.
+    pub no_break_after: Vec<String>,
+}
+
+impl BreakConfig {
+    /// Why the options conflict, if they ask for something unsupported.
+    pub fn conflict(&self) -> Option<&'static str> {
+        if !(0.0..=1.0).contains(&self.min_line_fill) {
+            Some("Minimum line fill must be between 0 and 1.")
+        } else if self.hyphenate && self.engine != BreakEngine::Greedy {
+            Some("Hyphenation only works with the greedy engine.")
+        } else if self.hyphenate && !self.language.english_hyphenation {
+            Some("Hyphenation only supports English.")
+        } else if self.min_line_fill > 0.0 && self.engine != BreakEngine::Greedy {
+            Some("Minimum line fill only works with the greedy engine.")
+        } else {
+            None
+        }
+    }
+
+    /// Width lines aim for among lines `line_width` wide.
+    pub fn goal_width(&self, line_width: usize) -> usize {
+        self.goal_width
+            .map_or(line_width, |goal_width| goal_width.min(line_width))
+    }
+
+    /// Length of the shortest lines to end at split points
+    /// among lines `line_width` wide,
+    /// clamping `min_line_fill` between 0 and 1.
+    pub fn min_line_len(&self, line_width: usize) -> usize {
+        (self.min_line_fill.clamp(0.0, 1.0) * line_width as f64) as usize
+    }
+
+    /// Tokens of `text` to break lines between.
+    pub fn tokens<'a>(&self, text: &'a str) -> Tokens<'a> {
+        Tokens::new(text, self.kinsoku).glued(self.glue.spans(text))
+    }
+
+    /// Whether lines may break after `word`, followed by `next`.
+    pub fn allows_break_after(&self, word: &str, next: Option<&str>) -> bool {
+        !self.language.no_break_words.contains(word)
+            && !is_number_before_unit(word, next)
+            && !self.no_break_after.iter().any(|w| w == word)
+    }
+
+    /// Sentence position of `token` followed by `next`,
+    /// including splits inside words.
+    pub fn token_position(&self, token: &Token, next: Option<&Token>) -> SentencePosition {
+        let mut chars = token.word.chars();
+        match (token.space_after, chars.next_back()) {
+            // Only a `-` between letters or digits is a hyphen.
+            (false, Some('-'))
+                if chars.next_back().is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(|next| next.word.starts_with(char::is_alphanumeric)) =>
+            {
+                SentencePosition::Hyphen
+            }
+            (false, Some(last_char)) if is_path_separator(last_char) => {
+                SentencePosition::PathSeparator
+            }
+            _ => self.sentence_position(token.word, next.map(|next| next.word)),
+        }
+    }
+
+    /// Sentence position of `word` followed by `next`.
+    pub fn sentence_position(&self, word: &str, next: Option<&str>) -> SentencePosition {
+        word_sentence_position_with(word, next, &self.language)
+    }
+
+    /// Where to break lines around `word` if it is a connection word.
+    pub fn connection_word_placement(&self, word: &str) -> Option<BreakPlacement> {
+        self.language
+            .connection_word_class(word)
+            .map(|class| self.connection_word_breaks.placement(class))
+    }
+}
+
+/// Algorithms to choose where to break lines in a paragraph.
+#[derive(clap::ValueEnum, Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BreakEngine {
+    /// Fill each line in turn, breaking at the best split point when
+    /// the line overflows.
+    #[default]
+    Greedy,
+    /// Choose all line breaks in a paragraph together to minimize
+    /// the total cost of split point penalties and raggedness,
+    /// like Knuth–Plass.
+    TotalFit,
+}
@@ -0,0 +1,73 @@
+use super::*;
+
+/// All options to format text with, reusable across many inputs and
+/// shareable across threads.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Formatter {
+    /// Lines are strictly narrower than this, if possible.
+    pub line_width: usize,
+    pub hanging_config: Hanging,
+    pub paragraph_starts: ParagraphStarts,
+    pub break_config: BreakConfig,
+}
+
+impl Formatter {
+    /// Formatter with lines narrower than `line_width` and default options.
+    pub fn new(line_width: usize) -> Self {
+        Self {
+            line_width,
+            hanging_config: Hanging::default(),
+            paragraph_starts: ParagraphStarts::default(),
+            break_config: BreakConfig::default(),
+        }
+    }
+
+    pub fn with_hanging_config(mut self, hanging_config: Hanging) -> Self {
+        self.hanging_config = hanging_config;
+        self
+    }
+
+    pub fn with_paragraph_starts(mut self, paragraph_starts: ParagraphStarts) -> Self {
+        self.paragraph_starts = paragraph_starts;
+        self
+    }
+
+    pub fn with_break_config(mut self, break_config: BreakConfig) -> Self {
+        self.break_config = break_config;
+        self
+    }
+
+    /// Format `text` into pieces to concatenate.
+    pub fn format<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut result = Vec::with_capacity(text.len() / 32);
+
+        for paragraph in ParagraphsIter::new(text, self.hanging_config, &self.paragraph_starts) {
+            debug!(?paragraph);
+            result.extend(paragraph.format(self.line_width, &self.break_config));
+        }
+
+        result
+    }
+
+    /// Format `text` into a string.
+    pub fn format_to_string(&self, text: &str) -> String {
+        self.format(text).concat()
+    }
+
+    /// Join each paragraph of `text` onto a single line, like [`unwrap`].
+    pub fn unwrap<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        unwrap(
+            text,
+            self.hanging_config,
+            &self.paragraph_starts,
+            &self.break_config,
+        )
+    }
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new(80)
+    }
+}
@@ -0,0 +1,61 @@
+use super::*;
+
+/// Regex's for spans of paragraphs never to break lines inside,
+/// such as inline code.
+/// Serialized as the list of patterns.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub struct Glue {
+    pub regex: Option<Patterns>,
+}
+
+const MARKDOWN_GLUE: [&str; 2] = ["`[^`]+`", r"!?\[[^\]]*\]\("];
+const LATEX_GLUE: [&str; 2] = [r"\$[^$]+\$", r"\\\((?s:.*?)\\\)"];
+
+impl Glue {
+    /// Byte ranges of the spans in `text` never to break lines inside.
+    pub fn spans(&self, text: &str) -> Vec<Range<usize>> {
+        match &self.regex {
+            Some(regex) => regex.find_iter(text).map(|span| span.range()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Patterns of configuration presets.
+    pub fn preset_patterns(markdown_friendly: bool, latex_friendly: bool) -> Vec<&'static str> {
+        let mut patterns = Vec::new();
+        if markdown_friendly {
+            patterns.extend(MARKDOWN_GLUE);
+        }
+        if latex_friendly {
+            patterns.extend(LATEX_GLUE);
+        }
+        patterns
+    }
+
+    /// Generate using configuration presets.
+    pub fn preset(markdown_friendly: bool, latex_friendly: bool) -> Result<Self, regex::Error> {
+        Self::try_from_str_slice(&Self::preset_patterns(markdown_friendly, latex_friendly))
+    }
+
+    pub fn try_from_str_slice(patterns: &[&str]) -> Result<Self, regex::Error> {
+        let regex = Patterns::new(patterns.iter().copied(), str::to_owned)?;
+        Ok(Self { regex })
+    }
+}
+
+impl TryFrom<Vec<String>> for Glue {
+    type Error = PatternError;
+
+    fn try_from(patterns: Vec<String>) -> Result<Self, Self::Error> {
+        let regex =
+            Patterns::new(patterns, str::to_owned).map_err(PatternError::in_field("glue"))?;
+        Ok(Self { regex })
+    }
+}
+
+impl From<Glue> for Vec<String> {
+    fn from(glue: Glue) -> Self {
+        patterns_of(&glue.regex)
+    }
+}
@@ -0,0 +1,120 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+/// Hyphenates words using Liang's patterns, like TeX.
+#[derive(Clone, Debug, Default)]
+pub struct Hyphenator {
+    /// Letters of each pattern and the values between them.
+    patterns: HashMap<String, Vec<u8>>,
+    /// Hyphenation points of words hyphenated differently from the patterns.
+    exceptions: HashMap<String, Vec<usize>>,
+    max_pattern_len: usize,
+}
+
+/// Minimum number of letters before a hyphen.
+const LEFT_MIN: usize = 2;
+/// Minimum number of letters after a hyphen.
+const RIGHT_MIN: usize = 3;
+
+impl Hyphenator {
+    /// Build a hyphenator from whitespace-separated TeX `patterns` like
+    /// `.ach4` and `exceptions` like `acad-e-my`.
+    pub fn new(patterns: &str, exceptions: &str) -> Self {
+        let mut hyphenator = Self::default();
+        for pattern in patterns.split_whitespace() {
+            let letters: String = pattern.chars().filter(|c| !c.is_ascii_digit()).collect();
+            let mut values = vec![0; letters.chars().count() + 1];
+            let mut index = 0;
+            for char in pattern.chars() {
+                match char.to_digit(10) {
+                    Some(value) => values[index] = value as u8,
+                    None => index += 1,
+                }
+            }
+            hyphenator.max_pattern_len = hyphenator.max_pattern_len.max(values.len() - 1);
+            hyphenator.patterns.insert(letters, values);
+        }
+        for exception in exceptions.split_whitespace() {
+            let mut points = Vec::new();
+            let mut n_letter = 0;
+            for char in exception.chars() {
+                match char {
+                    '-' => points.push(n_letter),
+                    _ => n_letter += 1,
+                }
+            }
+            hyphenator
+                .exceptions
+                .insert(exception.replace('-', "").to_lowercase(), points);
+        }
+        hyphenator
+    }
+
+    /// Hyphenator for American English.
+    pub fn english() -> &'static Self {
+        static ENGLISH: OnceLock<Hyphenator> = OnceLock::new();
+        ENGLISH.get_or_init(|| {
+            Self::new(
+                include_str!("data/hyph-en-us.pat.txt"),
+                include_str!("data/hyph-en-us.hyp.txt"),
+            )
+        })
+    }
+
+    /// Byte indexes in `word` where it can be hyphenated,
+    /// ignoring punctuation around it.
+    /// Words with other non-letters inside are never hyphenated.
+    pub fn hyphenation_points(&self, word: &str) -> Vec<usize> {
+        let start = word.find(|c: char| c.is_alphabetic()).unwrap_or(word.len());
+        let end = word
+            .rfind(|c: char| c.is_alphabetic())
+            .map_or(start, |index| {
+                index + word[index..].chars().next().map_or(0, char::len_utf8)
+            });
+        let letters = &word[start..end];
+        if !letters.chars().all(char::is_alphabetic) {
+            return Vec::new();
+        }
+        let lowercase = letters.to_lowercase();
+        let chars: Vec<char> = lowercase.chars().collect();
+        if chars.len() < LEFT_MIN + RIGHT_MIN || chars.len() != letters.chars().count() {
+            return Vec::new();
+        }
+        let points = match self.exceptions.get(&lowercase) {
+            Some(points) => points.clone(),
+            None => self.pattern_points(&chars),
+        };
+        let byte_indexes: Vec<usize> = letters.char_indices().map(|(index, _)| index).collect();
+        points
+            .into_iter()
+            .filter(|&point| LEFT_MIN <= point && point + RIGHT_MIN <= chars.len())
+            .map(|point| start + byte_indexes[point])
+            .collect()
+    }
+
+    /// Numbers of letters after which `chars` can be hyphenated
+    /// according to the patterns.
+    fn pattern_points(&self, chars: &[char]) -> Vec<usize> {
+        let dotted: Vec<char> = ['.']
+            .into_iter()
+            .chain(chars.iter().copied())
+            .chain(['.'])
+            .collect();
+        // `values[i]` is the value before `dotted[i]`.
+        let mut values = vec![0; dotted.len() + 1];
+        for start in 0..dotted.len() {
+            let mut key = String::new();
+            for &char in dotted[start..].iter().take(self.max_pattern_len) {
+                key.push(char);
+                if let Some(pattern_values) = self.patterns.get(&key) {
+                    for (offset, &value) in pattern_values.iter().enumerate() {
+                        values[start + offset] = values[start + offset].max(value);
+                    }
+                }
+            }
+        }
+        // Odd values before `dotted[i + 1]` allow hyphens after `i` letters.
+        (1..chars.len())
+            .filter(|&n_letter| values[n_letter + 1] % 2 == 1)
+            .collect()
+    }
+}
@@ -0,0 +1,129 @@
+use super::*;
+
+/// Kinsoku shori (禁則処理) rules for CJK characters that must not start or
+/// end a line.
+/// Opening brackets never end a line and
+/// closing brackets and punctuation never start a line under either rule.
+#[derive(clap::ValueEnum, Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kinsoku {
+    /// Also keep small kana, prolonged sound marks, iteration marks,
+    /// and CJK hyphens from starting a line.
+    #[default]
+    Strict,
+    /// Allow small kana, prolonged sound marks, iteration marks,
+    /// and CJK hyphens to start a line.
+    Loose,
+}
+
+impl Kinsoku {
+    pub fn prohibits_line_start(self, char: char) -> bool {
+        is_cjk_closing(char) || (self == Self::Strict && is_cjk_non_starter(char))
+    }
+
+    pub fn prohibits_line_end(self, char: char) -> bool {
+        is_cjk_opening(char)
+    }
+
+    pub fn prohibits_break_between(self, prev: char, next: char) -> bool {
+        self.prohibits_line_end(prev) || self.prohibits_line_start(next)
+    }
+}
+
+/// CJK opening brackets, which must not end a line.
+pub fn is_cjk_opening(char: char) -> bool {
+    matches!(
+        char,
+        '「' | '『'
+            | '（'
+            | '［'
+            | '｛'
+            | '〔'
+            | '【'
+            | '〖'
+            | '〘'
+            | '〚'
+            | '〈'
+            | '《'
+            | '｟'
+            | '〝'
+            | '｢'
+    )
+}
+
+/// CJK closing brackets and punctuation, which must not start a line.
+pub fn is_cjk_closing(char: char) -> bool {
+    matches!(
+        char,
+        '」' | '』'
+            | '）'
+            | '］'
+            | '｝'
+            | '〕'
+            | '】'
+            | '〗'
+            | '〙'
+            | '〛'
+            | '〉'
+            | '》'
+            | '｠'
+            | '〞'
+            | '〟'
+            | '｣'
+            | '、'
+            | '。'
+            | '，'
+            | '．'
+            | '：'
+            | '；'
+            | '！'
+            | '？'
+            | '・'
+            | '｡'
+            | '､'
+            | '･'
+    )
+}
+
+/// Small kana, prolonged sound marks, iteration marks, and CJK hyphens,
+/// which must not start a line under strict rules.
+pub fn is_cjk_non_starter(char: char) -> bool {
+    matches!(
+        char,
+        'ぁ' | 'ぃ'
+            | 'ぅ'
+            | 'ぇ'
+            | 'ぉ'
+            | 'っ'
+            | 'ゃ'
+            | 'ゅ'
+            | 'ょ'
+            | 'ゎ'
+            | 'ゕ'
+            | 'ゖ'
+            | 'ァ'
+            | 'ィ'
+            | 'ゥ'
+            | 'ェ'
+            | 'ォ'
+            | 'ッ'
+            | 'ャ'
+            | 'ュ'
+            | 'ョ'
+            | 'ヮ'
+            | 'ヵ'
+            | 'ヶ'
+            | '\u{31F0}'..='\u{31FF}' // Katakana Phonetic Extensions.
+            | '\u{FF67}'..='\u{FF70}' // Halfwidth small katakana and `ｰ`.
+            | 'ー'
+            | '々'
+            | '〻'
+            | 'ゝ'
+            | 'ゞ'
+            | 'ヽ'
+            | 'ヾ'
+            | '‐'
+            | '゠'
+            | '〜'
+    )
+}
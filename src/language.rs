@@ -0,0 +1,301 @@
+use std::collections::BTreeSet;
+
+use super::*;
+
+/// Languages with built-in [`LanguagePack`]s.
+#[derive(clap::ValueEnum, Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+}
+
+impl Language {
+    pub fn pack(self) -> LanguagePack {
+        match self {
+            Self::English => LanguagePack {
+                english_hyphenation: true,
+                ..LanguagePack::new(
+                    ENGLISH_CONJUNCTIONS,
+                    ENGLISH_SUBORDINATORS,
+                    ENGLISH_PREPOSITIONS,
+                    ENGLISH_ABBREVIATIONS,
+                    ENGLISH_NO_BREAK_WORDS,
+                )
+            },
+            Self::German => LanguagePack {
+                capitalized_nouns: true,
+                sub_sentence_ends: ['“', '‘', '«'].into(),
+                sub_sentence_starts: ['„', '‚', '»'].into(),
+                ..LanguagePack::new(
+                    GERMAN_CONJUNCTIONS,
+                    GERMAN_SUBORDINATORS,
+                    GERMAN_PREPOSITIONS,
+                    GERMAN_ABBREVIATIONS,
+                    GERMAN_NO_BREAK_WORDS,
+                )
+            },
+            Self::French => LanguagePack {
+                sub_sentence_ends: ['»'].into(),
+                sub_sentence_starts: ['«'].into(),
+                ..LanguagePack::new(
+                    FRENCH_CONJUNCTIONS,
+                    FRENCH_SUBORDINATORS,
+                    FRENCH_PREPOSITIONS,
+                    FRENCH_ABBREVIATIONS,
+                    FRENCH_NO_BREAK_WORDS,
+                )
+            },
+            Self::Spanish => LanguagePack {
+                sub_sentence_ends: ['»'].into(),
+                sub_sentence_starts: ['¿', '¡', '«'].into(),
+                ..LanguagePack::new(
+                    SPANISH_CONJUNCTIONS,
+                    SPANISH_SUBORDINATORS,
+                    SPANISH_PREPOSITIONS,
+                    SPANISH_ABBREVIATIONS,
+                    SPANISH_NO_BREAK_WORDS,
+                )
+            },
+        }
+    }
+}
+
+/// Words and punctuation of a language that decide where to break lines,
+/// on top of the punctuation built into [`word_sentence_position_with`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LanguagePack {
+    /// Coordinating conjunctions such as "and".
+    #[serde(default)]
+    pub conjunctions: BTreeSet<String>,
+    /// Words that start a clause, such as "because".
+    #[serde(default)]
+    pub subordinators: BTreeSet<String>,
+    /// Prepositions such as "of".
+    #[serde(default)]
+    pub prepositions: BTreeSet<String>,
+    /// Abbreviations, with their periods, that only end sentences before
+    /// capitalized words, unless they are also `no_break_words`.
+    #[serde(default)]
+    pub abbreviations: BTreeSet<String>,
+    /// Whether nouns are capitalized, so capitalization tells neither
+    /// abbreviations nor sentence starts; only `abbreviations` are then
+    /// abbreviations.
+    #[serde(default)]
+    pub capitalized_nouns: bool,
+    /// Words never to break lines after, such as articles.
+    #[serde(default)]
+    pub no_break_words: BTreeSet<String>,
+    /// Extra characters that end sentences.
+    #[serde(default)]
+    pub sentence_ends: BTreeSet<char>,
+    /// Extra characters that end sub-sentences, such as closing quotes.
+    #[serde(default)]
+    pub sub_sentence_ends: BTreeSet<char>,
+    /// Extra characters that start sub-sentences, such as opening quotes.
+    #[serde(default)]
+    pub sub_sentence_starts: BTreeSet<char>,
+    /// Whether words hyphenate with the American English patterns of
+    /// [`Hyphenator::english`].
+    #[serde(default)]
+    pub english_hyphenation: bool,
+}
+
+impl LanguagePack {
+    fn new(
+        conjunctions: &[&str],
+        subordinators: &[&str],
+        prepositions: &[&str],
+        abbreviations: &[&str],
+        no_break_words: &[&str],
+    ) -> Self {
+        let set = |words: &[&str]| words.iter().map(|word| word.to_string()).collect();
+        Self {
+            conjunctions: set(conjunctions),
+            subordinators: set(subordinators),
+            prepositions: set(prepositions),
+            abbreviations: set(abbreviations),
+            capitalized_nouns: false,
+            no_break_words: set(no_break_words),
+            sentence_ends: BTreeSet::new(),
+            sub_sentence_ends: BTreeSet::new(),
+            sub_sentence_starts: BTreeSet::new(),
+            english_hyphenation: false,
+        }
+    }
+
+    /// Parse a language pack from JSON with the kebab-case field names,
+    /// where missing fields are empty.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Also treat `abbreviations`, with their periods, as abbreviations.
+    pub fn with_abbreviations<S: Into<String>>(
+        mut self,
+        abbreviations: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.abbreviations
+            .extend(abbreviations.into_iter().map(Into::into));
+        self
+    }
+
+    /// Class of `word` if it connects different parts of a sentence.
+    pub fn connection_word_class(&self, word: &str) -> Option<ConnectionWordClass> {
+        if self.conjunctions.contains(word) {
+            Some(ConnectionWordClass::Conjunction)
+        } else if self.subordinators.contains(word) {
+            Some(ConnectionWordClass::Subordinator)
+        } else if self.prepositions.contains(word) {
+            Some(ConnectionWordClass::Preposition)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_sentence_separator(&self, char: char) -> bool {
+        is_sentence_separator(char) || self.sentence_ends.contains(&char)
+    }
+
+    pub fn is_sub_sentence_separator(&self, char: char) -> bool {
+        is_sub_sentence_separator(char) || self.sub_sentence_ends.contains(&char)
+    }
+
+    /// Closing quotes and brackets, including extra sub-sentence ends.
+    pub fn is_closing_punctuation(&self, char: char) -> bool {
+        is_closing_punctuation(char) || self.sub_sentence_ends.contains(&char)
+    }
+
+    pub fn is_sub_sentence_start(&self, char: char) -> bool {
+        is_sub_sentence_start(char) || self.sub_sentence_starts.contains(&char)
+    }
+}
+
+impl Default for LanguagePack {
+    fn default() -> Self {
+        Language::default().pack()
+    }
+}
+
+/// Serde form of a [`LanguagePack`] in other options:
+/// the name of its [`Language`] if it is a built-in one, or else the pack.
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum LanguageChoice {
+    Preset(Language),
+    Custom(LanguagePack),
+}
+
+impl From<&LanguagePack> for LanguageChoice {
+    fn from(pack: &LanguagePack) -> Self {
+        <Language as clap::ValueEnum>::value_variants()
+            .iter()
+            .find(|language| language.pack() == *pack)
+            .map_or_else(
+                || Self::Custom(pack.clone()),
+                |&language| Self::Preset(language),
+            )
+    }
+}
+
+impl From<LanguageChoice> for LanguagePack {
+    fn from(choice: LanguageChoice) -> Self {
+        match choice {
+            LanguageChoice::Preset(language) => language.pack(),
+            LanguageChoice::Custom(pack) => pack,
+        }
+    }
+}
+
+/// `#[serde(with)]` module for [`LanguagePack`] fields
+/// that serializes built-in packs by their [`Language`] name.
+pub(crate) mod preset_or_custom {
+    use serde::{Deserializer, Serializer};
+
+    use super::*;
+
+    pub fn serialize<S: Serializer>(pack: &LanguagePack, serializer: S) -> Result<S::Ok, S::Error> {
+        LanguageChoice::from(pack).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<LanguagePack, D::Error> {
+        LanguageChoice::deserialize(deserializer).map(Into::into)
+    }
+}
+
+const ENGLISH_CONJUNCTIONS: &[&str] = &["and", "or", "nor", "but", "except"];
+const ENGLISH_SUBORDINATORS: &[&str] = &[
+    "as", "since", "because", "so", "if", "then", "else", "than", "whether", "that", "which",
+    "who", "where", "when", "while", "though", "although",
+];
+const ENGLISH_PREPOSITIONS: &[&str] = &[
+    "in", "on", "of", "by", "for", "from", "to", "through", "with", "via",
+];
+const ENGLISH_ABBREVIATIONS: &[&str] = &[
+    "e.g.", "E.g.", "i.e.", "I.e.", "etc.", "approx.", "cf.", "Cf.", "vs.", "viz.", "al.", "resp.",
+    "incl.", "esp.", "ca.", "Eq.", "Eqs.", "Fig.", "Figs.", "Sec.", "Ch.", "Tab.", "No.", "Vol.",
+    "p.", "pp.",
+];
+const ENGLISH_NO_BREAK_WORDS: &[&str] = &[
+    "a", "an", "the", "A", "An", "The", "Mr.", "Mrs.", "Ms.", "Dr.", "Prof.", "St.", "Mt.", "Fig.",
+    "Figs.", "Eq.", "Eqs.", "Sec.", "Ch.", "Tab.", "Ref.", "Refs.", "No.", "Vol.", "p.", "pp.",
+    "e.g.", "E.g.", "i.e.", "I.e.", "cf.", "Cf.", "vs.", "viz.",
+];
+
+const GERMAN_CONJUNCTIONS: &[&str] = &["und", "oder", "aber", "sondern", "denn", "doch", "sowie"];
+const GERMAN_SUBORDINATORS: &[&str] = &[
+    "dass", "weil", "da", "wenn", "als", "ob", "obwohl", "damit", "während", "bevor", "nachdem",
+    "sobald", "falls", "welche", "welcher", "welches",
+];
+const GERMAN_PREPOSITIONS: &[&str] = &[
+    "in", "im", "an", "am", "auf", "aus", "bei", "mit", "nach", "von", "vom", "zu", "zum", "zur",
+    "für", "über", "unter", "durch", "gegen", "ohne", "um", "vor", "zwischen",
+];
+const GERMAN_ABBREVIATIONS: &[&str] = &[
+    "z.B.", "d.h.", "u.a.", "usw.", "bzw.", "ca.", "evtl.", "ggf.", "vgl.", "z.T.", "u.U.",
+    "i.d.R.", "bzgl.", "inkl.", "sog.", "etc.", "Nr.", "Dr.", "Hr.", "Fr.", "S.", "Abb.",
+];
+const GERMAN_NO_BREAK_WORDS: &[&str] = &[
+    "der", "die", "das", "den", "dem", "des", "ein", "eine", "einen", "einem", "einer", "eines",
+    "Der", "Die", "Das", "Ein", "Eine", "Dr.", "Hr.", "Fr.", "Nr.", "S.", "Abb.",
+];
+
+const FRENCH_CONJUNCTIONS: &[&str] = &["et", "ou", "mais", "ni", "car", "donc"];
+const FRENCH_SUBORDINATORS: &[&str] = &[
+    "que", "qui", "quand", "lorsque", "puisque", "comme", "si", "dont", "où", "parce", "quoique",
+];
+const FRENCH_PREPOSITIONS: &[&str] = &[
+    "de", "du", "des", "à", "au", "aux", "en", "dans", "par", "pour", "sur", "sous", "avec",
+    "sans", "vers", "chez", "entre",
+];
+const FRENCH_ABBREVIATIONS: &[&str] = &[
+    "p.", "ex.", "cf.", "etc.", "env.", "av.", "apr.", "J.-C.", "art.", "chap.", "éd.", "fig.",
+    "vol.", "resp.", "M.", "MM.", "Dr.",
+];
+const FRENCH_NO_BREAK_WORDS: &[&str] = &[
+    "le", "la", "les", "un", "une", "Le", "La", "Les", "Un", "Une", "M.", "MM.", "Mme", "Mlle",
+    "Dr.", "fig.", "p.",
+];
+
+const SPANISH_CONJUNCTIONS: &[&str] = &["y", "e", "o", "u", "ni", "pero", "sino"];
+const SPANISH_SUBORDINATORS: &[&str] = &[
+    "que", "porque", "cuando", "si", "aunque", "como", "donde", "mientras", "pues",
+];
+const SPANISH_PREPOSITIONS: &[&str] = &[
+    "a", "de", "del", "al", "en", "con", "por", "para", "sin", "sobre", "entre", "hacia", "hasta",
+    "desde",
+];
+const SPANISH_ABBREVIATIONS: &[&str] = &[
+    "Sr.", "Sra.", "Srta.", "Dr.", "Dra.", "Ud.", "Uds.", "etc.", "p.ej.", "pág.", "núm.",
+    "aprox.", "EE.UU.",
+];
+const SPANISH_NO_BREAK_WORDS: &[&str] = &[
+    "el", "la", "los", "las", "un", "una", "unos", "unas", "El", "La", "Los", "Las", "Un", "Una",
+    "Sr.", "Sra.", "Srta.", "Dr.", "Dra.",
+];
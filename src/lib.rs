@@ -1,29 +1,75 @@
-use std::str::Chars;
+use std::{ops::Range, str::Chars};
 
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use tailcall::tailcall;
 use tracing::{debug, trace};
 
+pub mod break_config;
+pub mod formatter;
+pub mod glue;
+pub mod hyphenation;
+pub mod kinsoku;
+pub mod language;
 pub mod paragraph_start;
 pub mod paragraphs;
+pub mod patterns;
+pub mod reference;
+pub mod report;
 pub mod split_points;
+pub mod tokens;
+pub mod total_fit;
+pub mod width;
+pub mod word_splits;
 pub mod words;
 
-pub use crate::{paragraph_start::ParagraphStarts, paragraphs::Hanging};
-use {paragraphs::*, split_points::*, words::*};
+pub use crate::{
+    break_config::{BreakConfig, BreakEngine},
+    formatter::Formatter,
+    glue::Glue,
+    hyphenation::Hyphenator,
+    kinsoku::Kinsoku,
+    language::{Language, LanguagePack},
+    paragraph_start::{ParagraphStartPatterns, ParagraphStarts},
+    paragraphs::Hanging,
+    patterns::{PatternError, Patterns},
+    reference::ReferenceBreaks,
+    report::{OverlongLine, Report},
+    split_points::{BreakPlacement, ConnectionWordBreaks, SplitPriorities},
+    width::WidthMeasure,
+};
+use {
+    kinsoku::*, paragraphs::*, patterns::*, split_points::*, tokens::*, total_fit::*,
+    word_splits::*, words::*,
+};
 
+/// Format `text` with the default [`BreakConfig`];
+/// see [`Formatter`] for the other options.
 pub fn format<'a>(
     text: &'a str,
     line_width: usize,
     hanging_config: Hanging,
     paragraph_starts: &'a ParagraphStarts,
+) -> Vec<&'a str> {
+    Formatter::new(line_width)
+        .with_hanging_config(hanging_config)
+        .with_paragraph_starts(paragraph_starts.clone())
+        .format(text)
+}
+
+/// Join each paragraph onto a single line, the inverse of [`format`].
+/// Ignored paragraphs are kept as is.
+pub fn unwrap<'a>(
+    text: &'a str,
+    hanging_config: Hanging,
+    paragraph_starts: &ParagraphStarts,
+    break_config: &BreakConfig,
 ) -> Vec<&'a str> {
     let mut result = Vec::with_capacity(text.len() / 32);
 
     for paragraph in ParagraphsIter::new(text, hanging_config, paragraph_starts) {
         debug!(?paragraph);
-        result.extend(paragraph.format(line_width));
+        result.extend(paragraph.unwrap(break_config));
     }
 
     result
@@ -31,6 +77,8 @@ pub fn format<'a>(
 
 #[cfg(feature = "py")]
 #[pyo3::pymodule]
+// `#[pyfunction]` converts the returned `PyErr` into itself.
+#[allow(clippy::useless_conversion)]
 mod _lowlevel {
     use pyo3::{exceptions::PyValueError, prelude::*};
 
@@ -41,6 +89,7 @@ mod _lowlevel {
     ///
     /// - `hanging_config` can be "disallow", "flatten", or "hang".
     ///
+    /// See `format_with` for the other options.
     /// See <https://github.com/SichangHe/fmtt> for the options.
     #[pyfunction]
     #[pyo3(signature = (
@@ -71,6 +120,73 @@ mod _lowlevel {
         Ok(formatted_words.join(""))
     }
 
+    /// Format text with all options given by `formatter`,
+    /// a `Formatter` serialized as JSON with kebab-case keys, e.g.,
+    /// `{"line-width": 60, "break-config": {"engine": "total-fit"}}`;
+    /// omitted options take their defaults.
+    ///
+    /// - `reference` is a previous version of `text` whose line breaks to
+    ///     reproduce.
+    ///
+    /// See <https://github.com/SichangHe/fmtt> for the options.
+    #[pyfunction]
+    #[pyo3(signature = (text, formatter="{}", reference=None))]
+    fn format_with(text: &str, formatter: &str, reference: Option<&str>) -> PyResult<String> {
+        let mut formatter: Formatter = serde_json::from_str(formatter)
+            .map_err(|why| PyValueError::new_err(format!("{why}")))?;
+        if let Some(conflict) = formatter.break_config.conflict() {
+            return Err(PyValueError::new_err(conflict));
+        }
+        formatter.break_config.reference =
+            reference.map(|reference| ReferenceBreaks::new(reference, &formatter.break_config));
+        Ok(formatter.format_to_string(text))
+    }
+
+    /// Parse a kebab-case option name such as "hang" into its enum.
+    fn value_enum_from_str<T: serde::de::DeserializeOwned>(value: &str) -> PyResult<T> {
+        serde_json::from_value(serde_json::Value::String(value.into()))
+            .map_err(|why| PyValueError::new_err(format!("{why}")))
+    }
+
+    /// Join each paragraph onto a single line, the inverse of `format`.
+    ///
+    /// See `format` for the options.
+    #[pyfunction]
+    #[pyo3(signature = (
+        text,
+        hanging_config="disallow",
+        single_line_starts=vec![],
+        multi_line_starts=vec![],
+        ignore_line_starts=vec![],
+        kinsoku="strict",
+    ))]
+    fn unwrap(
+        text: &str,
+        hanging_config: &str,
+        single_line_starts: Vec<String>,
+        multi_line_starts: Vec<String>,
+        ignore_line_starts: Vec<String>,
+        kinsoku: &str,
+    ) -> PyResult<String> {
+        let hanging_config = value_enum_from_str(hanging_config)?;
+        let paragraph_starts = ParagraphStarts::try_from_str_slices(
+            &borrowed_str_slice(&single_line_starts),
+            &borrowed_str_slice(&multi_line_starts),
+            &borrowed_str_slice(&ignore_line_starts),
+        )
+        .map_err(|why| PyValueError::new_err(format!("{why}")))?;
+        let break_config = BreakConfig {
+            kinsoku: value_enum_from_str(kinsoku)?,
+            ..Default::default()
+        };
+        let formatter = Formatter::default()
+            .with_hanging_config(hanging_config)
+            .with_paragraph_starts(paragraph_starts)
+            .with_break_config(break_config);
+        let unwrapped_words = formatter.unwrap(text);
+        Ok(unwrapped_words.join(""))
+    }
+
     fn borrowed_str_slice(slice: &[String]) -> Vec<&str> {
         slice.iter().map(String::as_str).collect()
     }
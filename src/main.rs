@@ -1,6 +1,11 @@
-use std::{fs::File, io::*, path::PathBuf};
+use std::{
+    fs::File,
+    io::*,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
 use fmtt::*;
 use tracing_subscriber::EnvFilter;
@@ -18,13 +23,11 @@ fn main() -> Result<()> {
         read_all(stdin())?
     };
 
-    let paragraph_starts = app.paragraph_starts()?;
-    let formatted = format(
-        &input,
-        app.line_width,
-        app.hanging_config(),
-        &paragraph_starts,
-    );
+    let formatter = app.formatter()?;
+    let formatted = match app.unwrap {
+        true => formatter.unwrap(&input),
+        false => formatter.format(&input),
+    };
 
     if let (true, Some(filename)) = (app.change_in_place, &app.filename) {
         write_all(File::create(filename)?, &formatted)?;
@@ -32,6 +35,16 @@ fn main() -> Result<()> {
         write_all(stdout(), &formatted)?;
     }
 
+    if app.report {
+        let report = Report::new(
+            &formatted.concat(),
+            formatter.line_width,
+            formatter.break_config.width_measure,
+        );
+        serde_json::to_writer(stderr(), &report)?;
+        eprintln!();
+    }
+
     Ok(())
 }
 
@@ -42,6 +55,28 @@ fn read_all(from: impl Read) -> Result<String> {
     Ok(String::from_utf8(input)?)
 }
 
+/// Read `filename` at Git revision `rev`.
+fn git_show(rev: &str, filename: &Path) -> Result<String> {
+    let Some(name) = filename.file_name() else {
+        bail!("Not a file.");
+    };
+    let mut command = Command::new("git");
+    command
+        .arg("show")
+        .arg(format!("{rev}:./{}", name.to_string_lossy()));
+    if let Some(parent) = filename
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        command.current_dir(parent);
+    }
+    let output = command.output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
 fn write_all(to: impl Write, formatted: &[&str]) -> Result<()> {
     let mut to = BufWriter::new(to);
     for blob in formatted {
@@ -52,6 +87,15 @@ fn write_all(to: impl Write, formatted: &[&str]) -> Result<()> {
     Ok(())
 }
 
+/// Parse a fraction between 0 and 1.
+fn parse_fraction(value: &str) -> std::result::Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(fraction) if (0.0..=1.0).contains(&fraction) => Ok(fraction),
+        Ok(_) => Err("must be between 0 and 1".into()),
+        Err(why) => Err(why.to_string()),
+    }
+}
+
 #[derive(Parser)]
 #[command(
     version,
@@ -80,6 +124,16 @@ struct App {
     )]
     line_width: usize,
 
+    #[arg(
+        short,
+        long,
+        help = r#"Line width to aim for, like `fmt -g`, preferring split points near it,
+and word boundaries near it if no other split point fits;
+lines still use the space up to the line width otherwise.
+Defaults to the line width."#
+    )]
+    goal_width: Option<usize>,
+
     #[arg(short, long, help = "Name of input file; if omitted, read from StdIn.")]
     filename: Option<PathBuf>,
 
@@ -118,9 +172,268 @@ treat `\` started lines as paragraph starts.
 Useful for LaTeX."#
     )]
     latex_friendly: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "columns",
+        help = "How to measure the width of words against the line width limit."
+    )]
+    width_measure: WidthMeasure,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "strict",
+        help = "Rules for CJK characters that must not start or end a line."
+    )]
+    kinsoku: Kinsoku,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value = "greedy",
+        help = "Algorithm to choose where to break lines."
+    )]
+    engine: BreakEngine,
+
+    #[arg(
+        long,
+        default_value = "2",
+        help = "Priority of splitting after sentence ends like `.`; 0 to ignore."
+    )]
+    end_priority: u8,
+
+    #[arg(
+        long,
+        default_value = "1",
+        help = "Priority of splitting after sub-sentence ends like `,`; 0 to ignore."
+    )]
+    sub_end_priority: u8,
+
+    #[arg(
+        long,
+        default_value = "1",
+        help = "Priority of splitting before sub-sentence starts like `(`; 0 to ignore."
+    )]
+    sub_start_priority: u8,
+
+    #[arg(
+        long,
+        default_value = "1",
+        help = "Priority of splitting after connection words like `and`; 0 to ignore."
+    )]
+    connection_word_priority: u8,
+
+    #[arg(
+        long,
+        default_value = "1",
+        help = "Priority of splitting after hyphens between letters or digits; 0 to ignore."
+    )]
+    hyphen_priority: u8,
+
+    #[arg(
+        long,
+        default_value = "1",
+        help = r#"Priority of splitting overlong words after `/`, `_`, `.`, or `-` not
+between letters or digits; 0 to ignore."#
+    )]
+    path_separator_priority: u8,
+
+    #[arg(
+        long,
+        default_value = "0",
+        value_parser = parse_fraction,
+        help = r#"Fraction of the line width, e.g., `0.5`, below which lines only end at
+split points if no split point further along fits. Only with the greedy engine."#
+    )]
+    min_line_fill: f64,
+
+    #[arg(
+        long,
+        default_value = "0",
+        help = r#"Number of columns lines may exceed the line width by to split at
+a split point of higher priority than all those within the width."#
+    )]
+    overflow: usize,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "after",
+        help = "Whether to split before or after conjunctions like `and`."
+    )]
+    conjunction_break: BreakPlacement,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "after",
+        help = "Whether to split before or after clause starters like `because`."
+    )]
+    subordinator_break: BreakPlacement,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "after",
+        help = "Whether to split before or after prepositions like `of`."
+    )]
+    preposition_break: BreakPlacement,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "english",
+        help = "Language for connection words, abbreviations and punctuation."
+    )]
+    language: Language,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "language",
+        help = r#"JSON file of a custom language pack to use instead of `--language`,
+with fields `conjunctions`, `subordinators`, `prepositions`, `abbreviations`,
+`capitalized-nouns`, `no-break-words`, `sentence-ends`,
+`sub-sentence-ends`, `sub-sentence-starts` and `english-hyphenation`."#
+    )]
+    language_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "WORD",
+        help = r#"Abbreviation, with its periods, that only ends sentences
+before capitalized words; can be repeated.
+Common ones such as "e.g." and "Fig." are built in."#
+    )]
+    abbreviation: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "File of whitespace-separated abbreviations, like `--abbreviation`."
+    )]
+    abbreviation_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value = "false",
+        help = r#"Keep existing line breaks if their lines still fit and
+end at split points;
+only re-flow lines that overflow or end badly."#
+    )]
+    stable: bool,
+
+    #[arg(
+        long,
+        help = r#"Previous version of the input file;
+reproduce its line breaks where the words around them are unchanged."#
+    )]
+    reference: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with = "reference",
+        requires = "filename",
+        help = r#"Git revision such as `HEAD` to read the previous version of
+the input file from, like `--reference`."#
+    )]
+    reference_rev: Option<String>,
+
+    #[arg(
+        long,
+        default_value = "false",
+        help = r#"Put each sentence on its own lines (semantic line breaks);
+still wrap long sentences to fit the line width."#
+    )]
+    sentence_per_line: bool,
+
+    #[arg(
+        long,
+        value_name = "MIN_WIDTH",
+        num_args = 0..=1,
+        default_missing_value = "20",
+        help = r#"Put each clause on its own lines: break after sentence ends, and after
+sub-sentence ends like `,` once the line is at least MIN_WIDTH (default 20)
+wide, so short clauses like "However," stay attached."#
+    )]
+    clause_per_line: Option<usize>,
+
+    #[arg(
+        short,
+        long,
+        default_value = "false",
+        help = "Only split lines that are too long; never join short lines, like `fmt -s`."
+    )]
+    split_only: bool,
+
+    #[arg(
+        short,
+        long,
+        default_value = "false",
+        help = r#"Join each paragraph onto a single line instead,
+e.g., for pasting into web forms."#
+    )]
+    unwrap: bool,
+
+    #[arg(
+        long,
+        default_value = "false",
+        conflicts_with = "unwrap",
+        help = r#"Print a JSON report to StdErr listing lines at least as wide as
+the line width, e.g., because of `--overflow`."#
+    )]
+    report: bool,
+
+    #[arg(
+        long,
+        help = r#"Regex for spans never to break lines inside; can be repeated.
+`--markdown-friendly` glues `` `code` `` and `[link text](`;
+`--latex-friendly` glues `$math$` and `\(math\)`."#
+    )]
+    glue: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "WORD",
+        help = r#"Word to never break lines after; can be repeated.
+Articles, titles such as "Dr.", and numbers before units are built in."#
+    )]
+    no_break_after: Vec<String>,
+
+    #[arg(
+        long,
+        default_value = "false",
+        help = r#"Allow splitting words after internal hyphens, e.g., `second-quarter`.
+Beware that Markdown and HTML render the line break as a space."#
+    )]
+    split_hyphens: bool,
+
+    #[arg(
+        long,
+        default_value = "false",
+        help = "Allow splitting paths, URLs, and identifiers too long for a line after `/`, `_`, `.` or `-`."
+    )]
+    split_long_words: bool,
+
+    #[arg(
+        long,
+        default_value = "false",
+        help = "Hyphenate English words when no split point fits a line. Only with the greedy engine and English."
+    )]
+    hyphenate: bool,
 }
 
 impl App {
+    fn formatter(&self) -> Result<Formatter> {
+        Ok(Formatter::new(self.line_width)
+            .with_hanging_config(self.hanging_config())
+            .with_paragraph_starts(self.paragraph_starts()?)
+            .with_break_config(self.break_config()?))
+    }
+
     fn paragraph_starts(&self) -> Result<ParagraphStarts> {
         ParagraphStarts::preset(self.markdown_friendly, self.latex_friendly)
             .context("Failed to build special paragraph starts handler.")
@@ -133,4 +446,81 @@ impl App {
             _ => Hanging::Disallow,
         }
     }
+
+    fn break_config(&self) -> Result<BreakConfig> {
+        let mut break_config = BreakConfig {
+            width_measure: self.width_measure,
+            kinsoku: self.kinsoku,
+            engine: self.engine,
+            priorities: SplitPriorities {
+                end: self.end_priority,
+                sub_end: self.sub_end_priority,
+                sub_start: self.sub_start_priority,
+                connection_word: self.connection_word_priority,
+                hyphen: self.hyphen_priority,
+                path_separator: self.path_separator_priority,
+            },
+            goal_width: self.goal_width,
+            min_line_fill: self.min_line_fill,
+            overflow: self.overflow,
+            connection_word_breaks: ConnectionWordBreaks {
+                conjunction: self.conjunction_break,
+                subordinator: self.subordinator_break,
+                preposition: self.preposition_break,
+            },
+            language: self.language()?,
+            stable: self.stable,
+            reference: None,
+            sentence_per_line: self.sentence_per_line,
+            clause_per_line: self.clause_per_line,
+            split_only: self.split_only,
+            glue: self.glue()?,
+            no_break_after: self.no_break_after.clone(),
+            split_hyphens: self.split_hyphens,
+            split_long_words: self.split_long_words,
+            hyphenate: self.hyphenate,
+        };
+        if let Some(conflict) = break_config.conflict() {
+            bail!("{conflict}");
+        }
+        break_config.reference = self
+            .reference_text()?
+            .map(|reference| ReferenceBreaks::new(&reference, &break_config));
+        Ok(break_config)
+    }
+
+    fn language(&self) -> Result<LanguagePack> {
+        let language = match &self.language_file {
+            Some(language_file) => LanguagePack::from_json(&read_all(File::open(language_file)?)?)
+                .with_context(|| format!("Failed to read language pack {language_file:?}."))?,
+            None => self.language.pack(),
+        };
+        let abbreviation_file = match &self.abbreviation_file {
+            Some(abbreviation_file) => {
+                read_all(File::open(abbreviation_file)?).with_context(|| {
+                    format!("Failed to read abbreviations from {abbreviation_file:?}.")
+                })?
+            }
+            None => String::new(),
+        };
+        Ok(language
+            .with_abbreviations(&self.abbreviation)
+            .with_abbreviations(abbreviation_file.split_whitespace()))
+    }
+
+    fn glue(&self) -> Result<Glue> {
+        let mut patterns = Glue::preset_patterns(self.markdown_friendly, self.latex_friendly);
+        patterns.extend(self.glue.iter().map(String::as_str));
+        Glue::try_from_str_slice(&patterns).context("Failed to build glue spans handler.")
+    }
+
+    fn reference_text(&self) -> Result<Option<String>> {
+        match (&self.reference, &self.reference_rev, &self.filename) {
+            (Some(reference), _, _) => Ok(Some(read_all(File::open(reference)?)?)),
+            (None, Some(rev), Some(filename)) => git_show(rev, filename)
+                .with_context(|| format!("Failed to read {filename:?} at revision `{rev}`."))
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
 }
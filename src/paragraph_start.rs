@@ -1,11 +1,22 @@
 use super::*;
 
 /// Regex's for determining special paragraph starts.
-#[derive(Clone, Debug, Default)]
+/// Serialized as [`ParagraphStartPatterns`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(try_from = "ParagraphStartPatterns", into = "ParagraphStartPatterns")]
 pub struct ParagraphStarts {
-    pub single_line: Option<Regex>,
-    pub multi_line: Option<Regex>,
-    pub ignore_line: Option<Regex>,
+    pub single_line: Option<Patterns>,
+    pub multi_line: Option<Patterns>,
+    pub ignore_line: Option<Patterns>,
+}
+
+/// Patterns of [`ParagraphStarts`], each matched after leading spaces.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ParagraphStartPatterns {
+    pub single_line: Vec<String>,
+    pub multi_line: Vec<String>,
+    pub ignore_line: Vec<String>,
 }
 
 const MARKDOWN_SINGLE_LINE_STARTS: [&str; 3] = ["#{1,6} ", r"---+[$\n]", r"===+[$\n]"];
@@ -57,18 +68,40 @@ impl ParagraphStarts {
         multi_line: &[&str],
         ignore_line: &[&str],
     ) -> Result<Self, regex::Error> {
-        let single_line = (!single_line.is_empty()).then_some(or_regex_from_strs(single_line)?);
-        let multi_line = (!multi_line.is_empty()).then_some(or_regex_from_strs(multi_line)?);
-        let ignore_line = (!ignore_line.is_empty()).then_some(or_regex_from_strs(ignore_line)?);
         Ok(Self {
-            single_line,
-            multi_line,
-            ignore_line,
+            single_line: Patterns::new(single_line.iter().copied(), line_start)?,
+            multi_line: Patterns::new(multi_line.iter().copied(), line_start)?,
+            ignore_line: Patterns::new(ignore_line.iter().copied(), line_start)?,
+        })
+    }
+}
+
+impl TryFrom<ParagraphStartPatterns> for ParagraphStarts {
+    type Error = PatternError;
+
+    fn try_from(patterns: ParagraphStartPatterns) -> Result<Self, Self::Error> {
+        Ok(Self {
+            single_line: Patterns::new(patterns.single_line, line_start)
+                .map_err(PatternError::in_field("single-line"))?,
+            multi_line: Patterns::new(patterns.multi_line, line_start)
+                .map_err(PatternError::in_field("multi-line"))?,
+            ignore_line: Patterns::new(patterns.ignore_line, line_start)
+                .map_err(PatternError::in_field("ignore-line"))?,
         })
     }
 }
 
+impl From<ParagraphStarts> for ParagraphStartPatterns {
+    fn from(paragraph_starts: ParagraphStarts) -> Self {
+        Self {
+            single_line: patterns_of(&paragraph_starts.single_line),
+            multi_line: patterns_of(&paragraph_starts.multi_line),
+            ignore_line: patterns_of(&paragraph_starts.ignore_line),
+        }
+    }
+}
+
 /// Leading spaces are allowed.
-fn or_regex_from_strs(slice: &[&str]) -> Result<Regex, regex::Error> {
-    Regex::new(&format!(r"^ *(:?{})", &slice.join("|")))
+fn line_start(pattern: &str) -> String {
+    format!(r"^ *(:?{pattern})")
 }
@@ -1,9 +1,9 @@
 use super::*;
 
-pub struct ParagraphsIter<'a> {
+pub struct ParagraphsIter<'a, 's> {
     text: &'a str,
     hanging_config: Hanging,
-    paragraph_starts: &'a ParagraphStarts,
+    paragraph_starts: &'s ParagraphStarts,
     next_is_single_paragraph: bool,
     next_is_ignore_paragraph: bool,
 }
@@ -26,11 +26,11 @@ pub enum Hanging {
     Hang,
 }
 
-impl<'a> ParagraphsIter<'a> {
+impl<'a, 's> ParagraphsIter<'a, 's> {
     pub fn new(
         text: &'a str,
         hanging_config: Hanging,
-        paragraph_starts: &'a ParagraphStarts,
+        paragraph_starts: &'s ParagraphStarts,
     ) -> Self {
         trace!(?hanging_config, ?paragraph_starts);
         Self {
@@ -58,7 +58,7 @@ impl<'a> ParagraphsIter<'a> {
     }
 }
 
-impl<'a> Iterator for ParagraphsIter<'a> {
+impl<'a> Iterator for ParagraphsIter<'a, '_> {
     type Item = Paragraph<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -76,7 +76,7 @@ impl<'a> Iterator for ParagraphsIter<'a> {
 #[allow(unreachable_code)]
 #[tailcall]
 fn iter_inner_next<'a>(
-    iter: &mut ParagraphsIter<'a>,
+    iter: &mut ParagraphsIter<'a, '_>,
     indentation: usize,
     next_new_line_index: usize,
 ) -> Option<Paragraph<'a>> {
@@ -177,67 +177,259 @@ const SPACES: &str =
     "                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                                ";
 
 impl<'a> Paragraph<'a> {
-    pub fn format(&self, line_width: usize) -> Vec<&'a str> {
+    pub fn format(&self, line_width: usize, break_config: &BreakConfig) -> Vec<&'a str> {
         if self.config.ignore {
             return vec![self.words];
         } else if self.words.is_empty() {
             return vec!["\n"];
         }
         let mut result = Vec::with_capacity(self.words.len() / 32);
-        paragraph_inner_format(
-            &mut self.config.clone(),
-            &mut (line_width + 1 - self.config.indentation),
-            &mut result,
-            &mut SplitPoints::default(),
-            &mut Vec::with_capacity(line_width / 2),
-            &mut 0,
-            &mut 0,
-            &mut self.words.split_ascii_whitespace(),
-            &mut 0,
+        let max_indentation = (self.config.hanging_indentation)
+            .map_or(self.config.indentation, |hanging| {
+                hanging.max(self.config.indentation)
+            });
+        let tokens = split_words(
+            break_config,
+            break_config.tokens(self.words),
+            line_width.saturating_sub(max_indentation),
         );
+        let segment_ends = segment_ends(break_config, &self.config, line_width, &tokens);
+        let mut config = self.config.clone();
+        let mut available_line_width = line_width + 1 - config.indentation;
+        let mut start = 0;
+        for end in segment_ends {
+            let segment = &tokens[start..end];
+            // Earlier segments may have started the hanging indentation.
+            let min_line_len = break_config.min_line_len(available_line_width - 1);
+            let goal_n_char =
+                (break_config.goal_width(line_width) + 1).saturating_sub(config.indentation);
+            let max_n_char = available_line_width;
+            match break_config.engine {
+                BreakEngine::Greedy => paragraph_inner_format(
+                    break_config,
+                    &mut config,
+                    &mut available_line_width,
+                    &mut result,
+                    &mut SplitPoints::new(
+                        break_config.priorities,
+                        min_line_len,
+                        goal_n_char,
+                        max_n_char,
+                    ),
+                    &mut Vec::with_capacity(line_width / 2),
+                    &mut 0,
+                    &mut 0,
+                    &mut segment.iter().copied(),
+                    &mut 0,
+                ),
+                BreakEngine::TotalFit => {
+                    total_fit_format(break_config, &mut config, line_width, segment, &mut result)
+                }
+            }
+            start = end;
+        }
+        result
+    }
+
+    /// Join the paragraph onto a single line.
+    pub fn unwrap(&self, break_config: &BreakConfig) -> Vec<&'a str> {
+        if self.config.ignore {
+            return vec![self.words];
+        } else if self.words.is_empty() {
+            return vec!["\n"];
+        }
+        let mut result = Vec::with_capacity(self.words.len() / 4);
+        let mut tokens = break_config.tokens(self.words).peekable();
+        if tokens.peek().is_some() {
+            push_line(&mut result, self.config.indentation, tokens);
+        }
         result
     }
 }
 
+/// Ends of the segments of `tokens` to format separately,
+/// each ending in a line break that is kept.
+pub fn segment_ends(
+    break_config: &BreakConfig,
+    config: &ParagraphConfig,
+    line_width: usize,
+    tokens: &[Token],
+) -> Vec<usize> {
+    let mut segment_ends = match break_config.stable {
+        true => stable_segment_ends(break_config, config, line_width, tokens),
+        false => vec![tokens.len()],
+    };
+    if let Some(reference) = &break_config.reference {
+        segment_ends.extend(reference.line_ends(tokens));
+    }
+    if break_config.sentence_per_line {
+        segment_ends.extend(sentence_ends(break_config, tokens));
+    }
+    if let Some(min_clause_width) = break_config.clause_per_line {
+        segment_ends.extend(clause_ends(break_config, tokens, min_clause_width));
+    }
+    if break_config.split_only {
+        segment_ends.extend(source_line_ends(tokens));
+    }
+    segment_ends.sort_unstable();
+    segment_ends.dedup();
+    segment_ends
+}
+
+/// Ends of the clauses in `tokens`, excluding the last token:
+/// sentence ends, and sub-sentence ends at least `min_clause_width` after
+/// the previous clause end, so short clauses like "However," stay attached.
+pub fn clause_ends(
+    break_config: &BreakConfig,
+    tokens: &[Token],
+    min_clause_width: usize,
+) -> Vec<usize> {
+    let mut clause_ends = Vec::new();
+    let mut clause_width = 0;
+    for (index, pair) in tokens.windows(2).enumerate() {
+        clause_width += pair[0].width(break_config.width_measure);
+        let is_clause_end = match break_config.sentence_position(pair[0].word, Some(pair[1].word)) {
+            SentencePosition::End => true,
+            SentencePosition::SubEnd => {
+                clause_width >= min_clause_width
+                    && break_config.allows_break_after(pair[0].word, Some(pair[1].word))
+            }
+            _ => false,
+        };
+        if is_clause_end {
+            clause_ends.push(index + 1);
+            clause_width = 0;
+        } else {
+            clause_width += pair[0].space_after as usize;
+        }
+    }
+    clause_ends
+}
+
+/// Ends of the lines in the source of `tokens`, excluding the last token.
+pub fn source_line_ends<'a>(tokens: &'a [Token]) -> impl Iterator<Item = usize> + 'a {
+    tokens[..tokens.len().saturating_sub(1)]
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| token.line_break_after)
+        .map(|(index, _)| index + 1)
+}
+
+/// Ends of the sentences in `tokens`, excluding the last token.
+pub fn sentence_ends<'a>(
+    break_config: &'a BreakConfig,
+    tokens: &'a [Token],
+) -> impl Iterator<Item = usize> + 'a {
+    tokens
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| {
+            break_config.sentence_position(pair[0].word, Some(pair[1].word))
+                == SentencePosition::End
+        })
+        .map(|(index, _)| index + 1)
+}
+
+/// Ends of the segments of `tokens` to format separately,
+/// keeping each line break in the source that ends a line that fits and
+/// sits at a split point.
+pub fn stable_segment_ends(
+    break_config: &BreakConfig,
+    config: &ParagraphConfig,
+    line_width: usize,
+    tokens: &[Token],
+) -> Vec<usize> {
+    let mut segment_ends = Vec::new();
+    let mut indentation = config.indentation;
+    let mut line_start = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        let line_end = index + 1;
+        if !token.line_break_after || line_end == tokens.len() {
+            continue;
+        }
+        let line_len = tokens[line_start..line_end]
+            .iter()
+            .map(|token| token.width(break_config.width_measure) + token.space_after as usize)
+            .sum::<usize>()
+            - token.space_after as usize;
+        let priorities = &break_config.priorities;
+        let position = break_position(break_config, tokens, line_end);
+        if indentation + line_len < line_width
+            && priorities.priority(position) > 0
+            && break_config.allows_break_after(token.word, tokens.get(line_end).map(|t| t.word))
+        {
+            segment_ends.push(line_end);
+        }
+        line_start = line_end;
+        indentation = config.hanging_indentation.unwrap_or(config.indentation);
+    }
+    segment_ends.push(tokens.len());
+    trace!(?segment_ends);
+    segment_ends
+}
+
+/// Push a line of `tokens` indented by `indentation` and ending in `\n`.
+pub fn push_line<'a>(
+    result: &mut Vec<&'a str>,
+    indentation: usize,
+    tokens: impl IntoIterator<Item = Token<'a>>,
+) {
+    result.push(&SPACES[..indentation]);
+    for token in tokens {
+        token.render_into(result);
+        result.push(if token.space_after { " " } else { "" });
+    }
+    debug!("Last word in line: {:?}.", result.last());
+    *result.last_mut().expect("We just pushed") = "\n";
+}
+
 /// Internal function used to format a paragraph.
 #[inline(always)]
 #[allow(unreachable_code, clippy::too_many_arguments)]
 #[tailcall]
 pub fn paragraph_inner_format<'a, I>(
+    break_config: &BreakConfig,
     config: &mut ParagraphConfig,
     available_line_width: &mut usize,
     result: &mut Vec<&'a str>,
     split_points: &mut SplitPoints,
-    to_be_split: &mut Vec<&'a str>,
+    to_be_split: &mut Vec<Token<'a>>,
     n_char: &mut usize,
     split_len: &mut usize,
     splits: &mut I,
     drain_index: &mut usize,
 ) where
-    I: Iterator<Item = &'a str>,
+    I: Iterator<Item = Token<'a>>,
 {
     trace!(n_char, split_len, drain_index, ?split_points, ?to_be_split);
 
     if *drain_index > 0 {
-        result.push(&SPACES[..config.indentation]);
-        for word in to_be_split.drain(..*drain_index) {
-            result.push(word);
-            result.push(" ");
-        }
-        debug!("Last word in line: {:?}.", result.last());
-        *result.last_mut().expect("We just pushed") = "\n";
+        push_line(
+            result,
+            config.indentation,
+            to_be_split.drain(..*drain_index),
+        );
         *drain_index = 0;
-        // Handle hanging:
-        if let Some(hanging_indentation) = config.hanging_indentation.take() {
-            *available_line_width -= hanging_indentation - config.indentation;
-            config.indentation = hanging_indentation;
-        }
-    } else if n_char < available_line_width || to_be_split.len() <= 1 {
-        if let Some(&split) = to_be_split.last() {
-            split_points.register_split(split, *split_len, to_be_split.len());
+        start_hanging(break_config, config, available_line_width, split_points);
+    } else if *n_char + line_end_padding(to_be_split)
+        < *available_line_width + break_config.overflow
+        || to_be_split.len() <= 1
+    {
+        // Register the last split only now that the split after it is known.
+        let next = splits.next();
+        if let Some(split) = to_be_split.last() {
+            let prev = to_be_split.len().checked_sub(2).map(|i| &to_be_split[i]);
+            split_points.register_split(
+                break_config,
+                prev,
+                split,
+                next.as_ref(),
+                *split_len,
+                to_be_split.len(),
+            );
         }
-        if let Some(split) = splits.next() {
-            *split_len = split.chars().count() + 1;
+        if let Some(split) = next {
+            *split_len = split.width(break_config.width_measure) + split.space_after as usize;
             to_be_split.push(split);
             *n_char += *split_len;
         } else {
@@ -245,16 +437,65 @@ pub fn paragraph_inner_format<'a, I>(
                 return;
             }
             *drain_index = to_be_split.len();
+            if *n_char + line_end_padding(to_be_split) >= *available_line_width {
+                // Overflowing by the tolerance; end earlier unless
+                // overflowing reaches a better split point.
+                match split_points.next() {
+                    Some(SplitPoint {
+                        index,
+                        n_char_after,
+                    }) => {
+                        *drain_index = index;
+                        *n_char = n_char_after;
+                    }
+                    None if to_be_split.len() > 1 => drain_before_last(
+                        break_config,
+                        *available_line_width,
+                        split_points,
+                        to_be_split,
+                        n_char,
+                        *split_len,
+                        drain_index,
+                    ),
+                    None => {}
+                }
+            }
         };
     } else {
+        // The last split overflows the line, but the line may end before it.
+        if let Some(split) = to_be_split.last() {
+            let prev = to_be_split.len().checked_sub(2).map(|i| &to_be_split[i]);
+            split_points.register_split_before(break_config, prev, split, to_be_split.len());
+        }
         match (split_len >= available_line_width, split_points.next()) {
+            (true, _) | (_, None)
+                if break_config.hyphenate
+                    && break_config.language.english_hyphenation
+                    && hyphenate_last(
+                        break_config,
+                        config,
+                        available_line_width,
+                        result,
+                        split_points,
+                        to_be_split,
+                        n_char,
+                        split_len,
+                    ) =>
+            {
+                split_points.reset();
+            }
             (true, _) | (_, None) => {
                 // Either the new split is too longer,
                 // or no valid split point was found.
-                // Drain the entire buffer once.
-                *drain_index = to_be_split.len().saturating_sub(1);
-                split_points.reset();
-                *n_char = *split_len;
+                drain_before_last(
+                    break_config,
+                    *available_line_width,
+                    split_points,
+                    to_be_split,
+                    n_char,
+                    *split_len,
+                    drain_index,
+                )
             }
             (
                 _,
@@ -270,6 +511,7 @@ pub fn paragraph_inner_format<'a, I>(
     }
 
     paragraph_inner_format(
+        break_config,
         config,
         available_line_width,
         result,
@@ -281,3 +523,139 @@ pub fn paragraph_inner_format<'a, I>(
         drain_index,
     )
 }
+
+/// Drain the entire buffer but its last token once,
+/// keeping words not to break after on the next line if they fit there.
+fn drain_before_last(
+    break_config: &BreakConfig,
+    available_line_width: usize,
+    split_points: &mut SplitPoints,
+    to_be_split: &[Token],
+    n_char: &mut usize,
+    split_len: usize,
+    drain_index: &mut usize,
+) {
+    *drain_index = to_be_split.len().saturating_sub(1);
+    split_points.reset();
+    *n_char = split_len;
+    while *drain_index > 1 {
+        let token = to_be_split[*drain_index - 1];
+        let token_len = token.width(break_config.width_measure) + token.space_after as usize;
+        let next = to_be_split[*drain_index].word;
+        if break_config.allows_break_after(token.word, Some(next))
+            || *n_char + token_len + line_end_padding(to_be_split) >= available_line_width
+        {
+            break;
+        }
+        *drain_index -= 1;
+        *n_char += token_len;
+    }
+    split_points.n_char = *n_char - split_len;
+}
+
+/// Indent lines after the first one by the hanging indentation, if any,
+/// and fit `split_points` to the narrower lines.
+fn start_hanging(
+    break_config: &BreakConfig,
+    config: &mut ParagraphConfig,
+    available_line_width: &mut usize,
+    split_points: &mut SplitPoints,
+) {
+    if let Some(hanging_indentation) = config.hanging_indentation.take() {
+        let line_width = *available_line_width + config.indentation - 1;
+        *available_line_width -= hanging_indentation - config.indentation;
+        config.indentation = hanging_indentation;
+        split_points.min_line_len = break_config.min_line_len(*available_line_width - 1);
+        split_points.max_n_char = *available_line_width;
+        split_points.goal_n_char =
+            (break_config.goal_width(line_width) + 1).saturating_sub(config.indentation);
+    }
+}
+
+/// Minimum number of letters on each side of an inserted hyphen.
+const MIN_HYPHENATED_LEN: usize = 4;
+
+/// Hyphenate the last token in `to_be_split` at the last point where
+/// the line up to the hyphen fits, push that line,
+/// and keep the rest of the token as the start of the next line.
+/// Whole words only, leaving glued tokens, code, and URLs intact.
+/// Return `false` and change nothing if no such point exists.
+#[allow(clippy::too_many_arguments)]
+fn hyphenate_last<'a>(
+    break_config: &BreakConfig,
+    config: &mut ParagraphConfig,
+    available_line_width: &mut usize,
+    result: &mut Vec<&'a str>,
+    split_points: &mut SplitPoints,
+    to_be_split: &mut Vec<Token<'a>>,
+    n_char: &mut usize,
+    split_len: &mut usize,
+) -> bool {
+    let Some(&last) = to_be_split.last() else {
+        return false;
+    };
+    // Pieces of split words have no space between them.
+    let whole_word = last.space_after
+        && to_be_split
+            .len()
+            .checked_sub(2)
+            .is_none_or(|index| to_be_split[index].space_after);
+    if !whole_word
+        || last.glued
+        || last.word.contains(['`', '$', '\\', '<', '>'])
+        || last.word.contains("://")
+    {
+        return false;
+    }
+    let n_letters = |word: &str| word.chars().filter(|c| c.is_alphabetic()).count();
+    // Counting the hyphen and the space after it.
+    let n_char_before = *n_char - *split_len + 2;
+    let point = Hyphenator::english()
+        .hyphenation_points(last.word)
+        .into_iter()
+        .rev()
+        .filter(|&point| {
+            n_letters(&last.word[..point]) >= MIN_HYPHENATED_LEN
+                && n_letters(&last.word[point..]) >= MIN_HYPHENATED_LEN
+        })
+        .find(|&point| {
+            let prefix = Token {
+                word: &last.word[..point],
+                ..last
+            };
+            n_char_before + prefix.width(break_config.width_measure) < *available_line_width
+        });
+    let Some(point) = point else {
+        return false;
+    };
+    debug!(word = last.word, point, "Hyphenating.");
+    let (prefix, suffix) = last.word.split_at(point);
+    to_be_split.pop();
+    to_be_split.push(Token {
+        word: prefix,
+        space_after: true,
+        line_break_after: false,
+        glued: false,
+    });
+    push_line(result, config.indentation, to_be_split.drain(..));
+    result.insert(result.len() - 1, "-");
+    start_hanging(break_config, config, available_line_width, split_points);
+    let suffix = Token {
+        word: suffix,
+        ..last
+    };
+    *split_len = suffix.width(break_config.width_measure) + suffix.space_after as usize;
+    *n_char = *split_len;
+    to_be_split.push(suffix);
+    true
+}
+
+/// `n_char` counts a space after every token in `to_be_split`;
+/// pad it for the last token if it has no space after it,
+/// so lines ending in CJK text get the same width limit.
+fn line_end_padding(to_be_split: &[Token]) -> usize {
+    match to_be_split.last() {
+        Some(last) if !last.space_after => 1,
+        _ => 0,
+    }
+}
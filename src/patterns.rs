@@ -0,0 +1,69 @@
+use std::{fmt, ops::Deref};
+
+use super::*;
+
+/// A regex compiled from a list of patterns,
+/// keeping the patterns to serialize.
+#[derive(Clone, Debug)]
+pub struct Patterns {
+    patterns: Vec<String>,
+    regex: Regex,
+}
+
+impl Patterns {
+    /// Compile `patterns` joined by `|` and then wrapped by `wrap`,
+    /// or `None` if there are no patterns.
+    pub fn new<S: Into<String>>(
+        patterns: impl IntoIterator<Item = S>,
+        wrap: fn(&str) -> String,
+    ) -> Result<Option<Self>, regex::Error> {
+        let patterns: Vec<String> = patterns.into_iter().map(Into::into).collect();
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+        let regex = Regex::new(&wrap(&patterns.join("|")))?;
+        Ok(Some(Self { patterns, regex }))
+    }
+
+    /// The patterns the regex is compiled from.
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+}
+
+impl Deref for Patterns {
+    type Target = Regex;
+
+    fn deref(&self) -> &Regex {
+        &self.regex
+    }
+}
+
+/// Patterns of `patterns`, if any.
+pub fn patterns_of(patterns: &Option<Patterns>) -> Vec<String> {
+    patterns
+        .as_ref()
+        .map_or_else(Vec::new, |patterns| patterns.patterns.clone())
+}
+
+/// An invalid regex in the configuration `field`.
+#[derive(Clone, Debug)]
+pub struct PatternError {
+    pub field: &'static str,
+    pub error: regex::Error,
+}
+
+impl PatternError {
+    /// Wrap errors about the regex's in `field`.
+    pub fn in_field(field: &'static str) -> impl Fn(regex::Error) -> Self {
+        move |error| Self { field, error }
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid `{}` regex: {}", self.field, self.error)
+    }
+}
+
+impl std::error::Error for PatternError {}
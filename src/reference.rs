@@ -0,0 +1,64 @@
+use std::{
+    collections::HashSet,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use super::*;
+
+/// Line breaks in a previous version of the text to reproduce when
+/// formatting.
+/// Each line break is identified by the two tokens before and after it,
+/// so breaks away from edits are still found.
+#[derive(Clone, Debug, Default)]
+pub struct ReferenceBreaks {
+    contexts: HashSet<u64>,
+}
+
+impl ReferenceBreaks {
+    /// Find the line breaks in `reference`, tokenized as configured in
+    /// `break_config`.
+    pub fn new(reference: &str, break_config: &BreakConfig) -> Self {
+        let tokens: Vec<_> = break_config.tokens(reference).collect();
+        let contexts = (1..tokens.len())
+            .filter(|&index| tokens[index - 1].line_break_after)
+            .map(|index| context_hash(&tokens, index))
+            .collect();
+        Self { contexts }
+    }
+
+    /// Whether the reference has a line break before `tokens[index]`.
+    pub fn has_break_before(&self, tokens: &[Token], index: usize) -> bool {
+        self.contexts.contains(&context_hash(tokens, index))
+    }
+
+    /// Ends of the lines in `tokens` that also end lines in the reference.
+    pub fn line_ends(&self, tokens: &[Token]) -> Vec<usize> {
+        (1..tokens.len())
+            .filter(|&index| self.has_break_before(tokens, index))
+            .collect()
+    }
+}
+
+/// Hash of the two tokens before and after the break before `tokens[index]`,
+/// ignoring whitespace inside tokens.
+fn context_hash(tokens: &[Token], index: usize) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let context_indexes = [
+        index.checked_sub(2),
+        index.checked_sub(1),
+        Some(index),
+        Some(index + 1),
+    ];
+    for context_index in context_indexes {
+        match context_index.and_then(|context_index| tokens.get(context_index)) {
+            Some(token) => {
+                true.hash(&mut hasher);
+                for piece in token.word.split_ascii_whitespace() {
+                    piece.hash(&mut hasher);
+                }
+            }
+            None => false.hash(&mut hasher),
+        }
+    }
+    hasher.finish()
+}
@@ -0,0 +1,35 @@
+use super::*;
+
+/// Facts about formatted text for tools such as CI to check.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Report {
+    /// Lines at least as wide as the line width, e.g., because of
+    /// [`BreakConfig::overflow`], long words, or ignored paragraphs.
+    pub overlong_lines: Vec<OverlongLine>,
+}
+
+impl Report {
+    /// Report on `formatted` text meant to be narrower than `line_width`.
+    pub fn new(formatted: &str, line_width: usize, width_measure: WidthMeasure) -> Self {
+        let overlong_lines = formatted
+            .lines()
+            .enumerate()
+            .map(|(index, line)| OverlongLine {
+                line: index + 1,
+                width: width_measure.width(line),
+            })
+            .filter(|line| line.width >= line_width)
+            .collect();
+        Self { overlong_lines }
+    }
+}
+
+/// A line at least as wide as the line width.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OverlongLine {
+    /// Line number, starting from 1.
+    pub line: usize,
+    /// Width of the line, including indentation.
+    pub width: usize,
+}
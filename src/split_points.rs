@@ -6,9 +6,42 @@ pub struct SplitPoints {
     pub end: SplitPoint,
     pub sub_end: SplitPoint,
     pub connection_word: SplitPoint,
+    pub hyphen: SplitPoint,
+    pub path_separator: SplitPoint,
+    /// Word boundary closest to the goal, if it is below the line width;
+    /// ranks below every other kind of split point.
+    pub word: SplitPoint,
+    pub priorities: SplitPriorities,
+    /// Split points leaving lines with at most this many characters are
+    /// only chosen if no other split point is found.
+    pub min_line_len: usize,
+    /// Among split points of the same kind or priority,
+    /// prefer those leaving lines with closest to this many characters.
+    pub goal_n_char: usize,
+    /// Split points leaving lines with at least this many characters
+    /// overflow the line, and are only chosen if their priority is higher
+    /// than that of all others.
+    pub max_n_char: usize,
+    /// Number of characters registered in the current line.
+    pub n_char: usize,
 }
 
 impl SplitPoints {
+    pub fn new(
+        priorities: SplitPriorities,
+        min_line_len: usize,
+        goal_n_char: usize,
+        max_n_char: usize,
+    ) -> Self {
+        Self {
+            priorities,
+            min_line_len,
+            goal_n_char,
+            max_n_char,
+            ..Default::default()
+        }
+    }
+
     /// Register chosen a split point with `n_char_after` characters after it.
     fn register_n_char_after(&mut self, n_char_after: usize) {
         for part in self.parts_ordered_mut() {
@@ -30,42 +63,132 @@ impl SplitPoints {
         }
     }
 
-    pub fn parts_ordered_mut(&mut self) -> [&mut SplitPoint; 4] {
+    pub fn parts_ordered_mut(&mut self) -> [&mut SplitPoint; 7] {
         [
             &mut self.end,
             &mut self.sub_end,
             &mut self.sub_start,
             &mut self.connection_word,
+            &mut self.hyphen,
+            &mut self.path_separator,
+            &mut self.word,
         ]
     }
 
-    pub fn register_split(&mut self, split: &str, split_len: usize, n_split: usize) {
+    /// Register only the split point before `split`, the `n_split`th split
+    /// in the line, after `prev`, while the split after it is unknown,
+    /// so the line can end before `split` if `split` overflows it.
+    pub fn register_split_before(
+        &mut self,
+        break_config: &BreakConfig,
+        prev: Option<&Token>,
+        split: &Token,
+        n_split: usize,
+    ) {
+        if prev.is_some_and(|prev| !break_config.allows_break_after(prev.word, Some(split.word))) {
+            return;
+        }
+        let index = n_split.saturating_sub(1);
+        match break_config.token_position(split, None) {
+            SentencePosition::SubStart => {
+                self.sub_start = self.closer_to_goal(self.sub_start, index, 0)
+            }
+            SentencePosition::ConnectionWord
+                if break_config.connection_word_placement(split.word)
+                    == Some(BreakPlacement::Before) =>
+            {
+                self.connection_word = self.closer_to_goal(self.connection_word, index, 0)
+            }
+            _ => {}
+        }
+    }
+
+    /// Register `split`, the `n_split`th split in the line,
+    /// between `prev` and `next`.
+    pub fn register_split(
+        &mut self,
+        break_config: &BreakConfig,
+        prev: Option<&Token>,
+        split: &Token,
+        next: Option<&Token>,
+        split_len: usize,
+        n_split: usize,
+    ) {
+        let break_before =
+            prev.is_none_or(|prev| break_config.allows_break_after(prev.word, Some(split.word)));
+        let break_after = break_config.allows_break_after(split.word, next.map(|next| next.word));
+        self.n_char += split_len;
         for part in self.parts_ordered_mut() {
             part.n_char_after += split_len;
         }
-        match word_sentence_position(split) {
-            SentencePosition::End => {
-                self.end.index = n_split;
-                self.end.n_char_after = 0;
-            }
+        let position = break_config.token_position(split, next);
+        if break_after && !position.is_inside_word() && self.goal_n_char < self.max_n_char {
+            self.word = self.closer_to_goal(self.word, n_split, 0);
+        }
+        match position {
+            SentencePosition::SubStart if !break_before => {}
+            SentencePosition::End
+            | SentencePosition::SubEnd
+            | SentencePosition::Hyphen
+            | SentencePosition::PathSeparator
+                if !break_after => {}
+            SentencePosition::End => self.end = self.closer_to_goal(self.end, n_split, 0),
             SentencePosition::SubEnd => {
-                self.sub_end.index = n_split;
-                self.sub_end.n_char_after = 0;
+                self.sub_end = self.closer_to_goal(self.sub_end, n_split, 0)
             }
             SentencePosition::SubStart => {
-                self.sub_start.index = n_split.saturating_sub(1);
-                self.sub_start.n_char_after = split_len;
+                self.sub_start =
+                    self.closer_to_goal(self.sub_start, n_split.saturating_sub(1), split_len)
             }
             SentencePosition::ConnectionWord => {
-                self.connection_word.index = n_split;
-                self.connection_word.n_char_after = 0;
+                match break_config.connection_word_placement(split.word) {
+                    Some(BreakPlacement::After) if break_after => {
+                        self.connection_word = self.closer_to_goal(self.connection_word, n_split, 0)
+                    }
+                    Some(BreakPlacement::Before) if break_before => {
+                        self.connection_word = self.closer_to_goal(
+                            self.connection_word,
+                            n_split.saturating_sub(1),
+                            split_len,
+                        )
+                    }
+                    _ => {}
+                }
+            }
+            SentencePosition::Hyphen => self.hyphen = self.closer_to_goal(self.hyphen, n_split, 0),
+            SentencePosition::PathSeparator => {
+                self.path_separator = self.closer_to_goal(self.path_separator, n_split, 0)
             }
             SentencePosition::Other => {}
         }
     }
 
+    /// Of the registered split point `old` and a new one at `index`
+    /// with `n_char_after` characters after it, the one of the same kind
+    /// leaving a line closer to the goal, preferring the new one on ties.
+    /// A new one overflowing the line never replaces one that fits.
+    fn closer_to_goal(&self, old: SplitPoint, index: usize, n_char_after: usize) -> SplitPoint {
+        let line_len = |n_char_after: usize| self.n_char.saturating_sub(n_char_after);
+        let goal_distance = |n_char_after: usize| line_len(n_char_after).abs_diff(self.goal_n_char);
+        let keep_old = (line_len(old.n_char_after) < self.max_n_char
+            && line_len(n_char_after) >= self.max_n_char)
+            || goal_distance(old.n_char_after) < goal_distance(n_char_after);
+        match old.index > 0 && keep_old {
+            true => old,
+            false => SplitPoint {
+                index,
+                n_char_after,
+            },
+        }
+    }
+
     pub fn reset(&mut self) {
-        *self = Self::default()
+        *self = Self::new(
+            self.priorities,
+            self.min_line_len,
+            self.goal_n_char,
+            self.max_n_char,
+        )
     }
 }
 
@@ -73,16 +196,46 @@ impl Iterator for SplitPoints {
     type Item = SplitPoint;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let candidates = self.parts_ordered_mut();
-        let maybe_best_split_point = if candidates[0].index > 0 {
-            Some(*candidates[0])
-        } else {
-            candidates
-                .into_iter()
-                .filter(|split_point| split_point.index > 0)
-                .map(|split_point| *split_point)
-                .max_by_key(|split_point| split_point.index)
-        };
+        let priorities = self.priorities;
+        // Split points inside words rank below others of the same priority.
+        let candidates = [
+            ((priorities.end, true), self.end),
+            ((priorities.sub_end, true), self.sub_end),
+            ((priorities.sub_start, true), self.sub_start),
+            ((priorities.connection_word, true), self.connection_word),
+            ((priorities.hyphen, false), self.hyphen),
+            ((priorities.path_separator, false), self.path_separator),
+        ]
+        .into_iter()
+        .filter(|((priority, _), _)| *priority > 0)
+        .chain([((0, false), self.word)])
+        .filter(|(_, split_point)| split_point.index > 0);
+        let line_len =
+            |split_point: &SplitPoint| self.n_char.saturating_sub(split_point.n_char_after);
+        let max_fitting_rank = candidates
+            .clone()
+            .filter(|(_, split_point)| line_len(split_point) < self.max_n_char)
+            .map(|(rank, _)| rank)
+            .max()
+            .unwrap_or_default();
+        // Only overflow to reach higher priorities.
+        // Prefer lines that are long enough, then higher priorities,
+        // then lines closer to the goal, then longer lines.
+        let maybe_best_split_point = candidates
+            .filter(|(rank, split_point)| {
+                line_len(split_point) < self.max_n_char || *rank > max_fitting_rank
+            })
+            .max_by_key(|(rank, split_point)| {
+                let long_enough = line_len(split_point) > self.min_line_len;
+                let goal_distance = line_len(split_point).abs_diff(self.goal_n_char);
+                (
+                    long_enough,
+                    *rank,
+                    std::cmp::Reverse(goal_distance),
+                    split_point.index,
+                )
+            })
+            .map(|(_, split_point)| split_point);
         maybe_best_split_point.map(
             |split_point @ SplitPoint {
                  index,
@@ -90,6 +243,7 @@ impl Iterator for SplitPoints {
              }| {
                 self.reduce_index(index);
                 self.register_n_char_after(n_char_after);
+                self.n_char = n_char_after;
                 split_point
             },
         )
@@ -101,3 +255,81 @@ pub struct SplitPoint {
     pub index: usize,
     pub n_char_after: usize,
 }
+
+/// Priorities of each kind of split point; higher priorities are preferred,
+/// and `0` ignores that kind of split point.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SplitPriorities {
+    pub end: u8,
+    pub sub_end: u8,
+    pub sub_start: u8,
+    pub connection_word: u8,
+    pub hyphen: u8,
+    pub path_separator: u8,
+}
+
+impl SplitPriorities {
+    pub fn priority(&self, position: SentencePosition) -> u8 {
+        match position {
+            SentencePosition::End => self.end,
+            SentencePosition::SubEnd => self.sub_end,
+            SentencePosition::SubStart => self.sub_start,
+            SentencePosition::ConnectionWord => self.connection_word,
+            SentencePosition::Hyphen => self.hyphen,
+            SentencePosition::PathSeparator => self.path_separator,
+            SentencePosition::Other => 0,
+        }
+    }
+
+    /// Highest priority among split points between words.
+    pub fn highest(&self) -> u8 {
+        self.end
+            .max(self.sub_end)
+            .max(self.sub_start)
+            .max(self.connection_word)
+    }
+}
+
+impl Default for SplitPriorities {
+    fn default() -> Self {
+        Self {
+            end: 2,
+            sub_end: 1,
+            sub_start: 1,
+            connection_word: 1,
+            hyphen: 1,
+            path_separator: 1,
+        }
+    }
+}
+
+/// Where to break lines around a connection word.
+#[derive(clap::ValueEnum, Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BreakPlacement {
+    /// End lines with the connection word.
+    #[default]
+    After,
+    /// Start lines with the connection word.
+    Before,
+}
+
+/// Where to break lines around each class of connection words.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ConnectionWordBreaks {
+    pub conjunction: BreakPlacement,
+    pub subordinator: BreakPlacement,
+    pub preposition: BreakPlacement,
+}
+
+impl ConnectionWordBreaks {
+    pub fn placement(&self, class: ConnectionWordClass) -> BreakPlacement {
+        match class {
+            ConnectionWordClass::Conjunction => self.conjunction,
+            ConnectionWordClass::Subordinator => self.subordinator,
+            ConnectionWordClass::Preposition => self.preposition,
+        }
+    }
+}
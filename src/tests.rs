@@ -58,25 +58,142 @@ fn latex_regex() {
 #[test]
 fn split_point_words() {
     use SentencePosition::*;
-    assert_eq!(SubStart, word_sentence_position("(though"));
-    assert_eq!(SubStart, word_sentence_position("[1"));
-    assert_eq!(SubEnd, word_sentence_position("and,"));
-    assert_eq!(SubEnd, word_sentence_position("Or,"));
-    assert_eq!(SubEnd, word_sentence_position("[1]"));
-    assert_eq!(SubEnd, word_sentence_position("so)"));
-    assert_eq!(End, word_sentence_position("A..Z."));
-    assert_eq!(End, word_sentence_position("Black)."));
-    assert_eq!(End, word_sentence_position("I18n."));
-    assert_eq!(End, word_sentence_position("A.n."));
-    assert_eq!(End, word_sentence_position("Program."));
-    assert_eq!(End, word_sentence_position("HMM."));
-    assert_eq!(End, word_sentence_position("(i.e."));
-    assert_eq!(Other, word_sentence_position("Mr."));
-    assert_eq!(Other, word_sentence_position("Ph.D."));
-    assert_eq!(Other, word_sentence_position("A.K.A."));
+    let english = LanguagePack::default();
+    // Word, the word after it, and the expected sentence position.
+    let cases = [
+        ("(though", None, SubStart),
+        ("[1", None, SubStart),
+        ("and,", None, SubEnd),
+        ("Or,", None, SubEnd),
+        ("[1]", None, SubEnd),
+        ("so)", None, SubEnd),
+        ("A..Z.", None, End),
+        ("Black).", None, End),
+        ("I18n.", None, End),
+        ("A.n.", None, End),
+        ("Program.", None, End),
+        ("HMM.", None, End),
+        ("(i.e.", None, SubStart),
+        ("(ie.", None, End),
+        ("Mr.", None, Other),
+        ("Ph.D.", None, Other),
+        ("A.K.A.", None, Other),
+        ("U.S.", None, Other),
+        ("Assoc.", None, Other),
+        ("Prof.", None, Other),
+        ("e.g.", None, Other),
+        ("etc.", None, Other),
+        ("approx.", None, Other),
+        ("cf.", None, Other),
+        ("Eq.", None, Other),
+        ("Equation.", None, End),
+        ("P.M.", Some("Then"), End),
+        ("P.M.", Some("tomorrow"), Other),
+        ("P.M.", Some("“Then"), End),
+        ("P.M.", Some("(Then"), End),
+        ("P.M.", Some("3"), Other),
+        ("U.S.", Some("It"), End),
+        ("U.S.", Some("government"), Other),
+        ("etc.", Some("The"), End),
+        ("etc.", Some("for"), Other),
+        ("cf.", Some("Fig."), Other),
+        ("(i.e.", Some("More"), SubStart),
+        ("Inc.", Some("They"), End),
+        ("Inc.", Some("they"), Other),
+        ("Mr.", Some("Smith"), Other),
+        ("Dr.", Some("Watson"), Other),
+        ("J.", Some("Smith"), Other),
+        ("Program.", Some("then"), End),
+        ("Program.", Some("Then"), End),
+        ("and", Some("The"), ConnectionWord),
+        ("end.\"", None, End),
+        ("done.)", None, End),
+        ("above).'", None, End),
+        ("this.”", None, End),
+        ("really?!)", None, End),
+        ("“Stop!”", None, End),
+        ("好。」", None, End),
+        ("好。』」", None, End),
+        ("好」", None, SubEnd),
+        ("」", None, SubEnd),
+        ("\"", Some("The"), SubEnd),
+        ("'", None, SubEnd),
+        ("`", None, SubEnd),
+        ("(Mr.)", None, SubEnd),
+        ("(Prof.", Some("Smith"), SubStart),
+        ("(e.g.)", Some("The"), SubEnd),
+        ("(U.S.)", Some("It"), End),
+    ];
+    for (word, next, expected) in cases {
+        assert_eq!(
+            expected,
+            word_sentence_position_with(word, next, &english),
+            "{word:?} before {next:?}"
+        );
+    }
+    assert_eq!(End, word_sentence_position("end."));
     assert_eq!(Other, word_sentence_position("U.S."));
-    assert_eq!(Other, word_sentence_position("Assoc."));
-    assert_eq!(Other, word_sentence_position("Prof."));
+    assert_eq!(ConnectionWord, word_sentence_position("because"));
+    assert!(is_connection_word("via"));
+    assert!(!is_connection_word("und"));
+}
+
+#[test]
+fn language_packs() {
+    use SentencePosition::*;
+    let german = Language::German.pack();
+    assert_eq!(Other, word_sentence_position_with("z.B.", None, &german));
+    assert_eq!(
+        Other,
+        word_sentence_position_with("z.B.", Some("Häuser"), &german)
+    );
+    assert_eq!(
+        SubStart,
+        word_sentence_position_with("(usw.", None, &german)
+    );
+    assert_eq!(End, word_sentence_position_with("Haus.", None, &german));
+    assert_eq!(
+        ConnectionWord,
+        word_sentence_position_with("und", None, &german)
+    );
+    assert_eq!(
+        SubStart,
+        word_sentence_position_with("„Hallo", None, &german)
+    );
+    assert_eq!(SubEnd, word_sentence_position_with("Welt“", None, &german));
+    assert_eq!(End, word_sentence_position_with("Welt.“", None, &german));
+    let french = Language::French.pack();
+    assert_eq!(Other, word_sentence_position_with("p.", None, &french));
+    assert_eq!(Other, word_sentence_position_with("ex.", None, &french));
+    assert_eq!(SubStart, word_sentence_position_with("«", None, &french));
+    assert_eq!(
+        ConnectionWord,
+        word_sentence_position_with("parce", None, &french)
+    );
+    let spanish = Language::Spanish.pack();
+    assert_eq!(Other, word_sentence_position_with("Sra.", None, &spanish));
+    assert_eq!(
+        SubStart,
+        word_sentence_position_with("¿Qué", None, &spanish)
+    );
+    assert_eq!(
+        ConnectionWord,
+        word_sentence_position_with("y", None, &spanish)
+    );
+    assert_eq!(Other, word_sentence_position_with("and", None, &spanish));
+
+    let custom = LanguagePack::from_json(
+        r#"{"conjunctions": ["ja"], "abbreviations": ["vrt."], "sentence-ends": ["‼"]}"#,
+    )
+    .expect("Valid language pack.");
+    assert_eq!(
+        ConnectionWord,
+        word_sentence_position_with("ja", None, &custom)
+    );
+    assert_eq!(Other, word_sentence_position_with("vrt.", None, &custom));
+    assert_eq!(End, word_sentence_position_with("Hei‼", None, &custom));
+    assert_eq!(Other, word_sentence_position_with("and", None, &custom));
+    assert!(LanguagePack::from_json(r#"{"conjunctions": "ja"}"#).is_err());
 }
 
 #[test]
@@ -104,3 +221,260 @@ fn init_tracing() {
         )
         .try_init();
 }
+
+#[test]
+fn width_measures() {
+    use WidthMeasure::*;
+    assert_eq!(Columns.width("fmtt"), 4);
+    assert_eq!(Chars.width("fmtt"), 4);
+    assert_eq!(Columns.width("中文"), 4);
+    assert_eq!(Chars.width("中文"), 2);
+    assert_eq!(Columns.width("（，）"), 6);
+    assert_eq!(Columns.width("e\u{301}"), 1);
+    assert_eq!(Chars.width("e\u{301}"), 2);
+    assert_eq!(Columns.width("a\u{200b}b"), 2);
+}
+
+#[test]
+fn cjk_tokens() {
+    let words = |text| {
+        Tokens::new(text, Kinsoku::Strict)
+            .map(|token| token.word)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(words("中文文本"), ["中", "文", "文", "本"]);
+    assert_eq!(words("你好，世界。"), ["你", "好，", "世", "界。"]);
+    assert_eq!(words("他说「好」。"), ["他", "说", "「好」。"]);
+    assert_eq!(words("使用Rust语言"), ["使", "用Rust语", "言"]);
+    assert_eq!(words("2024年"), ["2024年"]);
+    assert_eq!(words("中文。Done."), ["中", "文。", "Done."]);
+    assert_eq!(words("plain  words\n"), ["plain", "words"]);
+    assert_eq!(words("中文\n文本"), ["中", "文", "文", "本"]);
+    assert_eq!(words("中文\n。"), ["中", "文\n。"]);
+
+    let line_breaks = |text| {
+        Tokens::new(text, Kinsoku::Strict)
+            .map(|token| token.line_break_after)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(line_breaks("a b\nc"), [false, true, false]);
+    assert_eq!(line_breaks("中文\n文本"), [false, true, false, false]);
+
+    let rendered = |text| {
+        let mut result = Vec::new();
+        for token in Tokens::new(text, Kinsoku::Strict) {
+            token.render_into(&mut result);
+            result.push(if token.space_after { " " } else { "" });
+        }
+        result.join("")
+    };
+    assert_eq!(rendered("中文\n文本"), "中文文本 ");
+    assert_eq!(rendered("中文\n  。English\nwords"), "中文。English words ");
+    assert_eq!(rendered("中文 文本"), "中文 文本 ");
+}
+
+#[test]
+fn kinsoku_tokens() {
+    let words = |text, kinsoku| {
+        Tokens::new(text, kinsoku)
+            .map(|token| token.word)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(words("キャッシュ", Kinsoku::Strict), ["キャッ", "シュ"]);
+    assert_eq!(
+        words("キャッシュ", Kinsoku::Loose),
+        ["キ", "ャ", "ッ", "シ", "ュ"]
+    );
+    assert_eq!(words("コーヒー", Kinsoku::Strict), ["コー", "ヒー"]);
+    assert_eq!(words("「引用」", Kinsoku::Loose), ["「引", "用」"]);
+    assert_eq!(
+        words("文字 」です", Kinsoku::Loose),
+        ["文", "字 」", "で", "す"]
+    );
+    assert_eq!(words("書く（注", Kinsoku::Loose), ["書", "く", "（注"]);
+    assert!(Kinsoku::Loose.prohibits_line_start('。'));
+    assert!(Kinsoku::Strict.prohibits_line_start('ー'));
+    assert!(!Kinsoku::Loose.prohibits_line_start('ー'));
+    assert!(Kinsoku::Loose.prohibits_line_end('「'));
+}
+
+#[test]
+fn numbers_before_units() {
+    let break_config = BreakConfig::default();
+    assert!(!break_config.allows_break_after("3", Some("P.M.")));
+    assert!(!break_config.allows_break_after("1,000", Some("km,")));
+    assert!(!break_config.allows_break_after("10", Some("cm.")));
+    assert!(break_config.allows_break_after("3", Some("people")));
+    assert!(break_config.allows_break_after("6", Some("#######")));
+    assert!(break_config.allows_break_after("3", None));
+    assert!(break_config.allows_break_after("3cm", Some("long")));
+}
+
+#[test]
+fn word_split_positions() {
+    use SentencePosition::*;
+    let break_config = BreakConfig {
+        split_hyphens: true,
+        split_long_words: true,
+        ..Default::default()
+    };
+    let positions = |text: &str| {
+        let tokens = split_words(&break_config, Tokens::new(text, Kinsoku::default()), 8);
+        (0..tokens.len())
+            .map(|index| break_config.token_position(&tokens[index], tokens.get(index + 1)))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(vec![Hyphen, Other], positions("second-quarter"));
+    assert_eq!(
+        vec![PathSeparator, PathSeparator, Other],
+        positions("path/to/file")
+    );
+    assert_eq!(vec![PathSeparator, Hyphen, Other], positions("--long-flag"));
+}
+
+#[test]
+fn latex_glue_across_lines() {
+    let glue = Glue::preset(false, true).expect("Preset regex is incorrect.");
+    for text in ["See $a +\nb$ here.", "See \\(a +\nb\\) here."] {
+        let spans = glue.spans(text);
+        assert_eq!(spans.len(), 1, "{text:?}");
+        assert_eq!(
+            &text[spans[0].clone()],
+            &text[4..text.len() - 6],
+            "{text:?}"
+        );
+    }
+}
+
+#[test]
+fn hyphenation_points() {
+    let hyphenated = |word: &'static str| {
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        for point in Hyphenator::english().hyphenation_points(word) {
+            pieces.push(&word[start..point]);
+            start = point;
+        }
+        pieces.push(&word[start..]);
+        pieces.join("-")
+    };
+    assert_eq!(hyphenated("hyphenation"), "hy-phen-a-tion");
+    assert_eq!(hyphenated("Comprehensive,"), "Com-pre-hen-sive,");
+    assert_eq!(hyphenated("(imperative)"), "(im-per-a-tive)");
+    assert_eq!(hyphenated("project"), "pro-ject");
+    assert_eq!(hyphenated("the"), "the");
+    assert_eq!(hyphenated("state-of-the-art"), "state-of-the-art");
+    assert_eq!(hyphenated("https://example.com"), "https://example.com");
+}
+
+#[test]
+fn hyphenation_conflicts() {
+    let hyphenate = BreakConfig {
+        hyphenate: true,
+        ..Default::default()
+    };
+    assert_eq!(hyphenate.conflict(), None);
+    let total_fit = BreakConfig {
+        engine: BreakEngine::TotalFit,
+        ..hyphenate.clone()
+    };
+    assert!(total_fit.conflict().is_some());
+    let german = BreakConfig {
+        language: Language::German.pack(),
+        ..hyphenate.clone()
+    };
+    assert!(german.conflict().is_some());
+    let formatted = Formatter::new(10)
+        .with_break_config(german)
+        .format_to_string("Zusammenarbeit\n");
+    assert_eq!(formatted, "Zusammenarbeit\n");
+}
+
+#[test]
+fn formatter_reuse() {
+    fn assert_send_sync<T: Clone + Send + Sync>(_: &T) {}
+    let formatter = Formatter::new(40)
+        .with_hanging_config(Hanging::Hang)
+        .with_paragraph_starts(markdown_paragraph_starts())
+        .with_break_config(BreakConfig {
+            sentence_per_line: true,
+            ..Default::default()
+        });
+    assert_send_sync(&formatter);
+    let inputs = ["# Title\nHi. Bye.\n", "- An item.\nAnother sentence.\n"];
+    let formatted = std::thread::scope(|scope| {
+        inputs
+            .map(|input| scope.spawn(|| formatter.format_to_string(input)))
+            .map(|handle| handle.join().expect("Formatting panicked."))
+    });
+    assert_eq!(
+        formatted,
+        ["# Title\nHi.\nBye.\n", "- An item.\nAnother sentence.\n"]
+    );
+    let paragraph_starts = markdown_paragraph_starts();
+    let default_formatter = Formatter::new(40)
+        .with_hanging_config(Hanging::Hang)
+        .with_paragraph_starts(paragraph_starts.clone());
+    for input in inputs {
+        assert_eq!(
+            default_formatter.format_to_string(input),
+            format(input, 40, Hanging::Hang, &paragraph_starts).concat()
+        );
+    }
+}
+
+#[test]
+fn formatter_serde_round_trip() {
+    let formatter = Formatter::new(60)
+        .with_hanging_config(Hanging::Hang)
+        .with_paragraph_starts(markdown_paragraph_starts())
+        .with_break_config(BreakConfig {
+            engine: BreakEngine::TotalFit,
+            goal_width: Some(50),
+            clause_per_line: Some(10),
+            glue: Glue::preset(true, false).expect("Preset regex is incorrect."),
+            language: Language::German.pack(),
+            no_break_after: vec!["Apollo".into()],
+            ..Default::default()
+        });
+    let json = serde_json::to_string(&formatter).expect("Serializing failed.");
+    assert!(json.contains(r#""language":"german""#), "{json}");
+    let deserialized: Formatter = serde_json::from_str(&json).expect("Deserializing failed.");
+    assert_eq!(
+        serde_json::to_string(&deserialized).expect("Serializing failed."),
+        json
+    );
+    assert_eq!(
+        ParagraphStartPatterns::from(deserialized.paragraph_starts.clone()).multi_line,
+        ["[-*] ", r"\d+\. "]
+    );
+    let text = "# Header\n- Lists use `inline code`, and it is imperative that you bring your identification card.\n";
+    assert_eq!(
+        deserialized.format_to_string(text),
+        formatter.format_to_string(text)
+    );
+
+    let partial: Formatter =
+        serde_json::from_str(r#"{"line-width": 40, "break-config": {"sentence-per-line": true}}"#)
+            .expect("Deserializing failed.");
+    assert_eq!(partial.format_to_string("Hi. Bye.\n"), "Hi.\nBye.\n");
+
+    let custom = BreakConfig {
+        language: LanguagePack::default().with_abbreviations(["wrt."]),
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&custom).expect("Serializing failed.");
+    let deserialized: BreakConfig = serde_json::from_str(&json).expect("Deserializing failed.");
+    assert_eq!(deserialized.language, custom.language);
+    assert!(deserialized.language.abbreviations.contains("wrt."));
+
+    let invalid = r#"{"paragraph-starts": {"multi-line": ["[-*] ", "("]}}"#;
+    let why = serde_json::from_str::<Formatter>(invalid).expect_err("Regex should be invalid.");
+    assert!(
+        why.to_string().contains("invalid `multi-line` regex"),
+        "{why}"
+    );
+    let invalid = r#"{"break-config": {"glue": ["`[^`]+`", "["]}}"#;
+    let why = serde_json::from_str::<Formatter>(invalid).expect_err("Regex should be invalid.");
+    assert!(why.to_string().contains("invalid `glue` regex"), "{why}");
+}
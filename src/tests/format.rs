@@ -5,11 +5,55 @@ fn default_format(text: &str) -> String {
 }
 
 fn markdown_format(text: &str) -> String {
-    format(text, 80, Hanging::Hang, &markdown_paragraph_starts()).join("")
+    Formatter::new(80)
+        .with_hanging_config(Hanging::Hang)
+        .with_paragraph_starts(markdown_paragraph_starts())
+        .with_break_config(BreakConfig {
+            glue: Glue::preset(true, false).expect("Preset regex is incorrect."),
+            ..Default::default()
+        })
+        .format_to_string(text)
 }
 
 fn latex_format(text: &str) -> String {
-    format(text, 80, Default::default(), &latex_paragraph_starts()).join("")
+    Formatter::new(80)
+        .with_paragraph_starts(latex_paragraph_starts())
+        .with_break_config(BreakConfig {
+            glue: Glue::preset(false, true).expect("Preset regex is incorrect."),
+            ..Default::default()
+        })
+        .format_to_string(text)
+}
+
+/// Format `text` narrower than `line_width` without hanging.
+fn format_with(
+    text: &str,
+    line_width: usize,
+    paragraph_starts: &ParagraphStarts,
+    break_config: &BreakConfig,
+) -> String {
+    Formatter::new(line_width)
+        .with_paragraph_starts(paragraph_starts.clone())
+        .with_break_config(break_config.clone())
+        .format_to_string(text)
+}
+
+/// Words of `text`, joining words hyphenated across lines.
+fn unhyphenated_words(text: &str) -> Vec<String> {
+    text.replace("-\n", "")
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+fn markdown_unwrap(text: &str) -> String {
+    unwrap(
+        text,
+        Hanging::Hang,
+        &markdown_paragraph_starts(),
+        &Default::default(),
+    )
+    .join("")
 }
 
 macro_rules! t {
@@ -220,3 +264,785 @@ Lorem ipsum dolor sit amet, consectetur adipiscing elit. Sed do eiusmod tempor i
 Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.
 "#
 );
+
+#[test]
+fn width_measure_mixed_cjk() {
+    init_tracing();
+    let input = "我们的 文档 混合了 English 和 中文 所以 这些 段落 总是 排得 太宽 了 and the English part stays the same.\n";
+    let columns_formatted = format_with(
+        input,
+        40,
+        &Default::default(),
+        &BreakConfig {
+            width_measure: WidthMeasure::Columns,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&columns_formatted);
+    let chars_formatted = format_with(
+        input,
+        40,
+        &Default::default(),
+        &BreakConfig {
+            width_measure: WidthMeasure::Chars,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&chars_formatted);
+}
+
+t!(
+    cjk,
+    r#"
+天地玄黄，宇宙洪荒。日月盈昃，辰宿列张。寒来暑往，秋收冬藏。闰余成岁，律吕调阳。云腾致雨，露结为霜。金生丽水，玉出昆冈。剑号巨阙，珠称夜光。果珍李柰，菜重芥姜。
+
+吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。何でも薄暗いじめじめした所でニャーニャー泣いていた事だけは記憶している。
+
+中文与English混排的段落，在这里测试
+换行是否会被正确地合并，而不会插入多余的空格（这很重要）。
+"#
+);
+
+#[test]
+fn kinsoku_strict_and_loose() {
+    init_tracing();
+    let input = "ちょっとコーヒーショップでキャッシュレス決済を試してみたらスムーズだった。\n";
+    let strict_formatted = format_with(
+        input,
+        16,
+        &Default::default(),
+        &BreakConfig {
+            kinsoku: Kinsoku::Strict,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&strict_formatted);
+    let loose_formatted = format_with(
+        input,
+        16,
+        &Default::default(),
+        &BreakConfig {
+            kinsoku: Kinsoku::Loose,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&loose_formatted);
+}
+
+#[test]
+fn hanging_segments() {
+    init_tracing();
+    let paragraph = Paragraph {
+        config: ParagraphConfig {
+            ignore: false,
+            indentation: 0,
+            hanging_indentation: Some(4),
+        },
+        words: "First sentence here. Second sentence there. Third one.\n",
+    };
+    for engine in [BreakEngine::Greedy, BreakEngine::TotalFit] {
+        let break_config = BreakConfig {
+            engine,
+            sentence_per_line: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            paragraph.format(80, &break_config).concat(),
+            "First sentence here.\n    Second sentence there.\n    Third one.\n",
+            "{engine:?}"
+        );
+    }
+}
+
+#[test]
+fn total_fit_engine() {
+    init_tracing();
+    let input = r#"
+The department heads will convene at 3 P.M. to engage in a comprehensive discussion regarding the second-quarter budget, and it is imperative that you bring your identification card for seamless access; subsequently, the Information Technology team is scheduled to conduct a software demonstration at 4 P.M., with the esteemed presence of Mr. Chief Executive Officer's beautiful personal assistant.
+
+Lorem ipsum dolor sit amet, consectetur adipiscing elit. Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.
+
+- Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua, quis nostrud exercitation
+    ullamco laboris nisi ut aliquip ex ea commodo consequat. But, it chokes at very long splits such as `this_function_does_absolutely_nothing_i_am_afraid_but_it_needs_to_be_here_or_the_program_breaks`.
+"#
+    .trim_start();
+    let flattened = Formatter::new(80)
+        .with_hanging_config(Hanging::Flatten)
+        .with_paragraph_starts(markdown_paragraph_starts())
+        .with_break_config(BreakConfig {
+            engine: BreakEngine::TotalFit,
+            ..Default::default()
+        })
+        .format_to_string(input);
+    assert_snapshot!(&flattened);
+    let hanging = Paragraph {
+        config: ParagraphConfig {
+            ignore: false,
+            indentation: 0,
+            hanging_indentation: Some(2),
+        },
+        words: input.split("\n\n").last().unwrap(),
+    }
+    .format(
+        80,
+        &BreakConfig {
+            engine: BreakEngine::TotalFit,
+            ..Default::default()
+        },
+    )
+    .concat();
+    assert_snapshot!(&hanging);
+}
+
+#[test]
+fn custom_priorities() {
+    init_tracing();
+    let input = "In preparation for the impending meeting, a thorough review of the key performance indicators and return on investment is requested before the designated time of the meeting (which is set for 5 P.M.), and your prompt RSVP by 12 P.M. is kindly anticipated to ensure optimal (or at least as optimal as possible) coordination for the arrival of the very important persons at 2 P.M.! Furthermore, it is completely acceptable if you find yourself out of the office during this period.\n";
+    let ends_equal_priorities = SplitPriorities {
+        sub_end: 2,
+        connection_word: 0,
+        ..Default::default()
+    };
+    let ends_equal_formatted = format_with(
+        input,
+        80,
+        &Default::default(),
+        &BreakConfig {
+            priorities: ends_equal_priorities,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&ends_equal_formatted);
+    let all_equal_priorities = SplitPriorities {
+        end: 1,
+        ..Default::default()
+    };
+    let all_equal_formatted = format_with(
+        input,
+        80,
+        &Default::default(),
+        &BreakConfig {
+            priorities: all_equal_priorities,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&all_equal_formatted);
+}
+
+#[test]
+fn stable_keeps_line_breaks() {
+    init_tracing();
+    let input = r#"
+The department heads will convene at 3 P.M. to engage in
+a comprehensive and detailed discussion regarding the second-quarter budget, and
+it is imperative that you bring your identification card for seamless access;
+subsequently, the Information Technology team is scheduled to
+conduct a software demonstration at 4 P.M., with the esteemed presence of
+Mr. Chief Executive Officer's beautiful personal assistant.
+"#
+    .trim_start();
+    let greedy_formatted = format_with(
+        input,
+        80,
+        &Default::default(),
+        &BreakConfig {
+            stable: true,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&greedy_formatted);
+    let total_fit_formatted = format_with(
+        input,
+        80,
+        &Default::default(),
+        &BreakConfig {
+            engine: BreakEngine::TotalFit,
+            stable: true,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&total_fit_formatted);
+}
+
+#[test]
+fn reference_line_breaks() {
+    init_tracing();
+    let reference = r#"
+The department heads will convene at 3 P.M. to engage in
+a comprehensive discussion regarding the second-quarter budget, and
+it is imperative that you bring your identification card for seamless access;
+subsequently, the Information Technology team is scheduled to
+conduct a software demonstration at 4 P.M., with the esteemed presence of
+Mr. Chief Executive Officer's beautiful personal assistant.
+"#
+    .trim_start();
+    let input = "The department heads will convene at 3 P.M. to engage in a comprehensive discussion regarding the second-quarter budget, and it is imperative that you bring your card for seamless access; subsequently, the Information Technology team is scheduled to conduct a software demonstration at 4 P.M., with the esteemed presence of Mr. Chief Executive Officer's beautiful personal assistant.\n";
+    let break_config = BreakConfig {
+        reference: Some(ReferenceBreaks::new(reference, &Default::default())),
+        ..Default::default()
+    };
+    let formatted = format_with(input, 80, &Default::default(), &break_config);
+    assert_snapshot!(&formatted);
+    let self_formatted = format_with(reference, 80, &Default::default(), &break_config);
+    assert_eq!(self_formatted, reference);
+}
+
+#[test]
+fn sentence_per_line() {
+    init_tracing();
+    let input = r#"
+I asked (emailed Prof. He Who Must Not Be Named and CCed Prof. YouKnowWho). The former has a budget of 7500, and the latter 5000. Hi! The department heads will convene at 3 P.M. to engage in a comprehensive discussion regarding the second-quarter budget, and it is imperative that you bring your identification card for seamless access.
+- Lists are respected. So are their sentences.
+- Each item starts a new line.
+"#
+    .trim_start();
+    let formatted = format_with(
+        input,
+        80,
+        &markdown_paragraph_starts(),
+        &BreakConfig {
+            sentence_per_line: true,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&formatted);
+}
+
+#[test]
+fn clause_per_line() {
+    init_tracing();
+    let input = r#"
+However, the department heads will convene at 3 P.M.; bring your card, and your pen. Hi! The former has a budget of 7500, and the latter 5000: the difference, as we all know, is large.
+"#
+    .trim_start();
+    let formatted = format_with(
+        input,
+        80,
+        &latex_paragraph_starts(),
+        &BreakConfig {
+            clause_per_line: Some(20),
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&formatted);
+}
+
+#[test]
+fn split_only() {
+    init_tracing();
+    let input = r#"
+Roses are red,
+violets are blue,
+sugar is sweet, and so are you, said the poet who wrote very long lines.
+Stevens Hall
+123 Main Street
+"#
+    .trim_start();
+    let formatted = format_with(
+        input,
+        40,
+        &Default::default(),
+        &BreakConfig {
+            split_only: true,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&formatted);
+}
+
+#[test]
+fn no_break_after_words() {
+    init_tracing();
+    let input = r#"
+Yesterday we went over to see what the doctor said about the results. She
+went to see Dr. Watson twice today at 3 P.M. sharp and then waited at Apollo
+11 the whole time. Really. As Fig. 3 shows, the water level rose by 10 cm.
+
+Everybody here remembers visiting Dr. Watson. Everyone here remembers seeing
+Apollo 11 launch.
+"#
+    .trim_start();
+    let greedy_formatted = format_with(
+        input,
+        40,
+        &Default::default(),
+        &BreakConfig {
+            no_break_after: vec!["Apollo".into()],
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&greedy_formatted);
+    let total_fit_formatted = format_with(
+        input,
+        40,
+        &Default::default(),
+        &BreakConfig {
+            engine: BreakEngine::TotalFit,
+            no_break_after: vec!["Apollo".into()],
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&total_fit_formatted);
+}
+
+#[test]
+fn break_before_connection_words() {
+    init_tracing();
+    let input = r#"
+The committee reviewed the proposal in great detail and found several issues
+that need attention because the budget was incomplete and the timeline of the
+project was unrealistic for a team with only three engineers and one designer.
+"#
+    .trim_start();
+    let all_before = ConnectionWordBreaks {
+        conjunction: BreakPlacement::Before,
+        subordinator: BreakPlacement::Before,
+        preposition: BreakPlacement::Before,
+    };
+    let greedy_formatted = format_with(
+        input,
+        50,
+        &Default::default(),
+        &BreakConfig {
+            connection_word_breaks: all_before,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&greedy_formatted);
+    let total_fit_formatted = format_with(
+        input,
+        50,
+        &Default::default(),
+        &BreakConfig {
+            engine: BreakEngine::TotalFit,
+            connection_word_breaks: all_before,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&total_fit_formatted);
+    let conjunctions_before = ConnectionWordBreaks {
+        conjunction: BreakPlacement::Before,
+        ..Default::default()
+    };
+    let conjunctions_before_formatted = format_with(
+        input,
+        50,
+        &Default::default(),
+        &BreakConfig {
+            connection_word_breaks: conjunctions_before,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&conjunctions_before_formatted);
+    // The word overflowing the line may start the next one.
+    let overflowing_before_formatted = format_with(
+        "aaaa bbbb\nof cccc dddd eeee ffff gggg\nwith hhhh iiii\n",
+        40,
+        &Default::default(),
+        &BreakConfig {
+            connection_word_breaks: all_before,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        overflowing_before_formatted,
+        "aaaa bbbb of cccc dddd eeee ffff gggg\nwith hhhh iiii\n"
+    );
+}
+
+#[test]
+fn language_packs() {
+    init_tracing();
+    let german = r#"
+Die Daten werden z.B. täglich gesichert, d.h. wir verlieren höchstens einen Tag, und die Sicherungen liegen usw. auf einem separaten Server, weil das sicherer ist.
+"#
+    .trim_start();
+    let german_formatted = format_with(
+        german,
+        60,
+        &Default::default(),
+        &BreakConfig {
+            language: Language::German.pack(),
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&german_formatted);
+    let french = r#"
+Il a dit « nous partirons demain » mais, p. ex. en cas de pluie, nous resterons à la maison parce que la route est dangereuse.
+"#
+    .trim_start();
+    let french_formatted = format_with(
+        french,
+        60,
+        &Default::default(),
+        &BreakConfig {
+            language: Language::French.pack(),
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&french_formatted);
+    let spanish = r#"
+La Sra. García preguntó ¿vamos a salir hoy? y nadie respondió porque todos estaban ocupados con el trabajo de la oficina.
+"#
+    .trim_start();
+    let spanish_formatted = format_with(
+        spanish,
+        60,
+        &Default::default(),
+        &BreakConfig {
+            language: Language::Spanish.pack(),
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&spanish_formatted);
+}
+
+#[test]
+fn abbreviations() {
+    init_tracing();
+    let input = r#"
+Caches help, e.g. for reads, i.e. most requests. Latency wrt. disk drops by approx. half (cf. Fig. 2 and Eq. 3). Writes, logs, etc. are unaffected.
+"#
+    .trim_start();
+    let formatted = format_with(
+        input,
+        80,
+        &Default::default(),
+        &BreakConfig {
+            sentence_per_line: true,
+            language: LanguagePack::default().with_abbreviations(["wrt."]),
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&formatted);
+}
+
+#[test]
+fn abbreviation_sentence_ends() {
+    init_tracing();
+    let input = r#"
+He arrived at 3 P.M. Then he left. We meet at 3 P.M. tomorrow with J. Smith from the U.S. embassy. They moved to the U.S. It was far.
+"#
+    .trim_start();
+    let formatted = format_with(
+        input,
+        80,
+        &markdown_paragraph_starts(),
+        &BreakConfig {
+            sentence_per_line: true,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&formatted);
+}
+
+#[test]
+fn split_words() {
+    init_tracing();
+    let input = r#"
+We shipped a state-of-the-art second-quarter release with well-tested code.
+But, it chokes at very long splits such as `this_function_does_absolutely_nothing_i_am_afraid_but_it_needs_to_be_here_or_the_program_breaks`.
+See https://github.com/SichangHe/internet_route_verification/assets/84777573/11f8ad38-403c-4e5d-99da-66176795223f for details.
+"#
+    .trim_start();
+    let greedy_formatted = format_with(
+        input,
+        40,
+        &Default::default(),
+        &BreakConfig {
+            split_hyphens: true,
+            split_long_words: true,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&greedy_formatted);
+    let total_fit_formatted = format_with(
+        input,
+        40,
+        &Default::default(),
+        &BreakConfig {
+            engine: BreakEngine::TotalFit,
+            split_hyphens: true,
+            split_long_words: true,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&total_fit_formatted);
+}
+
+#[test]
+fn min_line_fill() {
+    init_tracing();
+    let input = r#"
+Hi. The heads of the department will convene at noon, and bring your card.
+Sure. It is imperative that you bring your identification card for access.
+"#
+    .trim_start();
+    let unfilled = format_with(
+        input,
+        40,
+        &Default::default(),
+        &BreakConfig {
+            min_line_fill: 0.0,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&unfilled);
+    let filled = format_with(
+        input,
+        40,
+        &Default::default(),
+        &BreakConfig {
+            min_line_fill: 0.3,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&filled);
+    let hanging = Paragraph {
+        config: ParagraphConfig {
+            ignore: false,
+            indentation: 0,
+            hanging_indentation: Some(6),
+        },
+        words: input,
+    };
+    let hanging_filled = hanging
+        .format(
+            40,
+            &BreakConfig {
+                min_line_fill: 0.3,
+                ..Default::default()
+            },
+        )
+        .concat();
+    assert_snapshot!(&hanging_filled);
+    let total_fit = BreakConfig {
+        engine: BreakEngine::TotalFit,
+        min_line_fill: 0.3,
+        ..Default::default()
+    };
+    assert!(total_fit.conflict().is_some());
+    for min_line_fill in [-0.5, 1.5, f64::NAN] {
+        let break_config = BreakConfig {
+            min_line_fill,
+            ..Default::default()
+        };
+        assert!(break_config.conflict().is_some(), "{min_line_fill}");
+        assert_eq!(
+            format_with(input, 40, &Default::default(), &break_config),
+            format_with(
+                input,
+                40,
+                &Default::default(),
+                &BreakConfig {
+                    min_line_fill: min_line_fill.clamp(0.0, 1.0),
+                    ..Default::default()
+                }
+            ),
+            "{min_line_fill}"
+        );
+    }
+}
+
+#[test]
+fn overflow() {
+    init_tracing();
+    let input = r#"
+We reviewed the budget and the schedule today. Then we left, and the office closed for a week.
+We reviewed the budget and the schedule of the team today.
+"#
+    .trim_start();
+    let strict = format_with(
+        input,
+        45,
+        &Default::default(),
+        &BreakConfig {
+            overflow: 0,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&strict);
+    assert_eq!(
+        Report::new(&strict, 45, WidthMeasure::Columns),
+        Report::default()
+    );
+    let overflowing = format_with(
+        input,
+        45,
+        &Default::default(),
+        &BreakConfig {
+            overflow: 2,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&overflowing);
+    let report = Report::new(&overflowing, 45, WidthMeasure::Columns);
+    assert_eq!(report.overlong_lines, [OverlongLine { line: 1, width: 46 }]);
+    // Overflowing needs a higher priority than the split points that fit,
+    // even at the end of the paragraph.
+    for (input, line_width, expected) in [
+        (
+            "aaaa bbbb cccc dddd eeee ffff, gggg hhhh iiii jjjjj, kkkk llll mmmm\n",
+            52,
+            "aaaa bbbb cccc dddd eeee ffff,\ngggg hhhh iiii jjjjj, kkkk llll mmmm\n",
+        ),
+        (
+            "aaaa bbbb cccc dddd eeee ffff gggg hhhh\n",
+            38,
+            "aaaa bbbb cccc dddd eeee ffff gggg\nhhhh\n",
+        ),
+    ] {
+        let break_config = BreakConfig {
+            overflow: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_with(input, line_width, &Default::default(), &break_config),
+            expected
+        );
+    }
+}
+
+#[test]
+fn goal_width() {
+    init_tracing();
+    let input = r#"
+The department heads will convene at 3 P.M. to engage in a comprehensive discussion regarding the second-quarter budget, and it is imperative that you bring your identification card for seamless access to the conference room, which is on the fifth floor of the building, next to the elevators.
+"#
+    .trim_start();
+    let greedy_formatted = format_with(
+        input,
+        80,
+        &Default::default(),
+        &BreakConfig {
+            goal_width: Some(60),
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&greedy_formatted);
+    let total_fit_formatted = format_with(
+        input,
+        80,
+        &Default::default(),
+        &BreakConfig {
+            engine: BreakEngine::TotalFit,
+            goal_width: Some(60),
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&total_fit_formatted);
+    // Every line but the last lands within 10 columns of the goal,
+    // and those lines average within 5 columns of it.
+    let assert_near_goal = |formatted: &str, goal: usize| {
+        let lines: Vec<_> = formatted.lines().map(str::len).collect();
+        let (last, lines) = lines.split_last().expect("No lines formatted.");
+        assert!(*last <= goal + 10, "{formatted}");
+        assert!(
+            lines.iter().all(|len| len.abs_diff(goal) <= 10),
+            "{formatted}"
+        );
+        let mean = lines.iter().sum::<usize>() / lines.len();
+        assert!(mean.abs_diff(goal) <= 5, "{formatted}");
+    };
+    assert_near_goal(&greedy_formatted, 60);
+    assert_near_goal(&total_fit_formatted, 60);
+    let hanging_paragraph = Paragraph {
+        config: ParagraphConfig {
+            ignore: false,
+            indentation: 0,
+            hanging_indentation: Some(12),
+        },
+        words: "The team met in the office of the director to talk about the plan for the year and the budget for the new building in the city, and then they went to the park with the dogs of the neighbors for a walk in the sun before the rain came down on all of them.\n",
+    };
+    let hanging_formatted = hanging_paragraph
+        .format(
+            80,
+            &BreakConfig {
+                goal_width: Some(60),
+                ..Default::default()
+            },
+        )
+        .concat();
+    assert_snapshot!(&hanging_formatted);
+    assert_near_goal(&hanging_formatted, 60);
+    // Without other split points, lines end at word boundaries near the goal.
+    let plain = "Lorem ipsum dolor sit amet consectetur adipiscing elit sed do eiusmod tempor incididunt ut labore et dolore magna aliqua ut enim ad minim veniam quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat duis aute irure dolor\n";
+    let plain_formatted = format_with(
+        plain,
+        80,
+        &Default::default(),
+        &BreakConfig {
+            goal_width: Some(50),
+            ..Default::default()
+        },
+    );
+    assert_near_goal(&plain_formatted, 50);
+}
+
+#[test]
+fn hyphenate() {
+    init_tracing();
+    let input = r#"
+The department heads will convene to engage in a comprehensive discussion regarding the budget.
+Internationalization is imperative, yet uncharacteristically overlooked.
+"#
+    .trim_start();
+    let formatted = format_with(
+        input,
+        24,
+        &Default::default(),
+        &BreakConfig {
+            hyphenate: true,
+            ..Default::default()
+        },
+    );
+    assert_snapshot!(&formatted);
+    assert_eq!(unhyphenated_words(&formatted), unhyphenated_words(input));
+    for input in [
+        "Call the function `internationalization` instead.\n",
+        "Call the function <internationalization> instead.\n",
+        "Read https://example.com/internationalization instead.\n",
+    ] {
+        let formatted = format_with(
+            input,
+            30,
+            &Default::default(),
+            &BreakConfig {
+                hyphenate: true,
+                ..Default::default()
+            },
+        );
+        assert!(!formatted.contains("-\n"), "{formatted}");
+    }
+}
+
+#[test]
+fn unwrap_and_format_back() {
+    init_tracing();
+    let input = r#"
+# Header 1
+The department heads will convene at 3 P.M. to engage in
+a comprehensive discussion regarding the second-quarter budget, and
+it is imperative that you bring your identification card for seamless access.
+- Lists are respected.
+- These two are not merged into one line,
+but each item is unwrapped.
+
+    Indented paragraphs keep their indentation
+    when unwrapped.
+
+中文与English混排的段落，在这里测试
+换行是否会被正确地合并。
+"#
+    .trim_start();
+    let unwrapped = markdown_unwrap(input);
+    assert_snapshot!(&unwrapped);
+    assert_eq!(markdown_format(&unwrapped), markdown_format(input));
+}
+
+t!(
+    glue,
+    r#"
+Run `cargo build --workspace && cargo test --workspace` before pushing, then read [the contributing guide for new contributors](https://example.com/contributing) carefully. Recall that $a + b = c$ implies \(c - b = a\) for all real numbers we care about here.
+"#
+);
@@ -0,0 +1,208 @@
+use super::*;
+
+/// Iterator over the [`Token`]s of a paragraph.
+///
+/// Tokens are split at ASCII whitespace and at break opportunities between
+/// CJK characters, in the spirit of
+/// [UAX #14](https://www.unicode.org/reports/tr14/).
+#[derive(Clone, Debug)]
+pub struct Tokens<'a> {
+    text: &'a str,
+    kinsoku: Kinsoku,
+    /// Byte ranges of the original text never to break inside.
+    glue_spans: Vec<Range<usize>>,
+    /// Byte offset of `text` in the original text.
+    offset: usize,
+}
+
+impl<'a> Tokens<'a> {
+    pub fn new(text: &'a str, kinsoku: Kinsoku) -> Self {
+        Self {
+            text,
+            kinsoku,
+            glue_spans: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    /// Never break inside `glue_spans` of the text.
+    pub fn glued(self, glue_spans: Vec<Range<usize>>) -> Self {
+        Self { glue_spans, ..self }
+    }
+
+    fn is_glued(&self, index: usize) -> bool {
+        self.glue_spans
+            .iter()
+            .any(|span| span.start < index && index < span.end)
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self
+            .text
+            .trim_start_matches(|c: char| c.is_ascii_whitespace());
+        let offset = self.offset + self.text.len() - text.len();
+        let mut chars = text.char_indices().peekable();
+        let (_, mut prev) = chars.next()?;
+        let (end, space_after, line_break_after) = loop {
+            let Some((index, char)) = chars.next() else {
+                break (text.len(), true, false);
+            };
+            if char.is_ascii_whitespace() {
+                let n_whitespace = text[index..]
+                    .find(|c: char| !c.is_ascii_whitespace())
+                    .unwrap_or(text.len() - index);
+                let whitespace = &text[index..index + n_whitespace];
+                match text[index + n_whitespace..].chars().next() {
+                    _ if self.is_glued(offset + index) => {
+                        while chars.next_if(|(_, c)| c.is_ascii_whitespace()).is_some() {}
+                    }
+                    Some(next) if separator(prev, whitespace, next).is_empty() => {
+                        if is_cjk_break_opportunity(prev, next, self.kinsoku) {
+                            break (index, false, true);
+                        }
+                        // A line break inside unbreakable CJK text.
+                        while chars.next_if(|(_, c)| c.is_ascii_whitespace()).is_some() {}
+                    }
+                    Some(next) if self.kinsoku.prohibits_break_between(prev, next) => {
+                        while chars.next_if(|(_, c)| c.is_ascii_whitespace()).is_some() {}
+                    }
+                    _ => break (index, true, whitespace.contains('\n')),
+                }
+            } else if !self.is_glued(offset + index)
+                && is_cjk_break_opportunity(prev, char, self.kinsoku)
+            {
+                break (index, false, false);
+            } else {
+                prev = char;
+            }
+        };
+        self.text = &text[end..];
+        let glued = self
+            .glue_spans
+            .iter()
+            .any(|span| span.start < offset + end && offset < span.end);
+        self.offset = offset + end;
+        Some(Token {
+            word: &text[..end],
+            space_after,
+            line_break_after,
+            glued,
+        })
+    }
+}
+
+/// A piece of a paragraph that is never broken, such as a word or
+/// an ideograph.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Token<'a> {
+    /// Source text of the token; may contain whitespace that is dropped or
+    /// normalized when rendered.
+    pub word: &'a str,
+    /// Whether a space follows this token if the line continues after it.
+    pub space_after: bool,
+    /// Whether a line break follows this token in the source text.
+    pub line_break_after: bool,
+    /// Whether the token overlaps a glue span, so it is never split.
+    pub glued: bool,
+}
+
+impl<'a> Token<'a> {
+    /// Push the rendered token, not including the space after it.
+    pub fn render_into(&self, result: &mut Vec<&'a str>) {
+        for (piece, separator) in pieces(self.word) {
+            result.push(piece);
+            if !separator.is_empty() {
+                result.push(separator);
+            }
+        }
+    }
+
+    /// Width of the rendered token, not including the space after it.
+    pub fn width(&self, width_measure: WidthMeasure) -> usize {
+        pieces(self.word)
+            .map(|(piece, separator)| width_measure.width(piece) + separator.len())
+            .sum()
+    }
+}
+
+/// Whitespace-separated pieces of `word`,
+/// each with the separator to render after it.
+fn pieces(word: &str) -> impl Iterator<Item = (&str, &'static str)> {
+    let mut rest = word;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let end = rest
+            .find(|c: char| c.is_ascii_whitespace())
+            .unwrap_or(rest.len());
+        let (piece, after) = rest.split_at(end);
+        let next_start = after
+            .find(|c: char| !c.is_ascii_whitespace())
+            .unwrap_or(after.len());
+        let (whitespace, next) = after.split_at(next_start);
+        rest = next;
+        let separator = match (piece.chars().next_back(), next.chars().next()) {
+            (Some(prev), Some(next)) => separator(prev, whitespace, next),
+            _ => "",
+        };
+        Some((piece, separator))
+    })
+}
+
+/// Separator to render for `whitespace` between `prev` and `next`.
+/// Line breaks between CJK characters are dropped instead of becoming spaces.
+pub fn separator(prev: char, whitespace: &str, next: char) -> &'static str {
+    match whitespace.contains('\n') && is_cjk(prev) && is_cjk(next) {
+        true => "",
+        false => " ",
+    }
+}
+
+/// Whether a line can break between `prev` and `next` without whitespace:
+/// between ideographs, before opening brackets, and after CJK punctuation,
+/// unless `kinsoku` or trailing ASCII punctuation prohibits it.
+pub fn is_cjk_break_opportunity(prev: char, next: char, kinsoku: Kinsoku) -> bool {
+    if kinsoku.prohibits_break_between(prev, next)
+        || is_sub_sentence_separator(next)
+        || is_sentence_separator(next)
+    {
+        return false;
+    }
+    is_cjk_closing(prev)
+        || (is_cjk_ideograph(prev) && (is_cjk_ideograph(next) || is_cjk_opening(next)))
+}
+
+pub fn is_cjk(char: char) -> bool {
+    is_cjk_ideograph(char) || is_cjk_opening(char) || is_cjk_closing(char)
+}
+
+/// Ideographs, kana, and other CJK characters that are written without
+/// spaces between them.
+pub fn is_cjk_ideograph(char: char) -> bool {
+    matches!(
+        char,
+        '\u{2E80}'..='\u{2FDF}' // CJK Radicals, Kangxi Radicals.
+            | '\u{3005}'..='\u{3007}' // `々`, `〆`, `〇`.
+            | '\u{3021}'..='\u{3029}' // Hangzhou numerals.
+            | '\u{3031}'..='\u{303C}' // Kana repeat marks.
+            | '\u{3040}'..='\u{309F}' // Hiragana.
+            | '\u{30A0}'..='\u{30FA}' // Katakana.
+            | '\u{30FC}'..='\u{30FF}' // Katakana prolonged sound mark, etc.
+            | '\u{3100}'..='\u{312F}' // Bopomofo.
+            | '\u{3190}'..='\u{31FF}' // Kanbun, CJK Strokes, Katakana ext.
+            | '\u{3200}'..='\u{33FF}' // Enclosed CJK, CJK Compatibility.
+            | '\u{3400}'..='\u{4DBF}' // CJK Unified Ideographs Extension A.
+            | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs.
+            | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs.
+            | '\u{FF10}'..='\u{FF19}' // Fullwidth digits.
+            | '\u{FF21}'..='\u{FF3A}' // Fullwidth Latin capital letters.
+            | '\u{FF41}'..='\u{FF5A}' // Fullwidth Latin small letters.
+            | '\u{FF66}'..='\u{FF9F}' // Halfwidth Katakana.
+            | '\u{20000}'..='\u{3FFFF}' // Supplementary Ideographic Planes.
+    )
+}
@@ -0,0 +1,142 @@
+use super::*;
+
+/// Columns of unused line width that a split point is worth
+/// per priority level above it.
+const PRIORITY_COLUMNS: u64 = 20;
+
+/// Penalty for breaking at a split point at `position`,
+/// in the same unit as raggedness: squared columns of unused line width.
+/// Split points of the highest priority are free;
+/// split points inside words cost half a priority level more.
+pub fn break_penalty(priorities: &SplitPriorities, position: SentencePosition) -> u64 {
+    let rank = priorities
+        .highest()
+        .saturating_sub(priorities.priority(position)) as u64;
+    let inside_word = match position.is_inside_word() {
+        true => PRIORITY_COLUMNS / 2,
+        false => 0,
+    };
+    (rank * PRIORITY_COLUMNS + inside_word).pow(2)
+}
+
+/// Penalty for a line that cannot fit, i.e., a single token too long.
+const OVERFLOW_PENALTY: u64 = 1 << 32;
+
+/// Penalty for breaking after a word not to break after.
+const NO_BREAK_PENALTY: u64 = 1 << 24;
+
+/// Split point position of a line break between `tokens[index - 1]` and
+/// `tokens[index]`, choosing the one of higher priority if both words make
+/// split points.
+pub fn break_position(
+    break_config: &BreakConfig,
+    tokens: &[Token],
+    index: usize,
+) -> SentencePosition {
+    use SentencePosition::*;
+    let priorities = &break_config.priorities;
+    let (before, after) = (&tokens[index - 1], &tokens[index]);
+    let after_next = tokens.get(index + 1);
+    let placement = |token: &Token| break_config.connection_word_placement(token.word);
+    let position_before = match break_config.token_position(before, Some(after)) {
+        SubStart => Other,
+        ConnectionWord if placement(before) == Some(BreakPlacement::Before) => Other,
+        position => position,
+    };
+    let position_after = match break_config.token_position(after, after_next) {
+        SubStart => SubStart,
+        ConnectionWord if placement(after) == Some(BreakPlacement::Before) => ConnectionWord,
+        _ => Other,
+    };
+    match priorities.priority(position_after) > priorities.priority(position_before) {
+        true => position_after,
+        false => position_before,
+    }
+}
+
+/// Format a paragraph of `tokens` by choosing the line breaks that minimize
+/// the total cost over the whole paragraph.
+/// Each line break costs its split point penalty plus
+/// the square of the line's distance from the goal width;
+/// the last line is free.
+pub fn total_fit_format<'a>(
+    break_config: &BreakConfig,
+    config: &mut ParagraphConfig,
+    line_width: usize,
+    tokens: &[Token<'a>],
+    result: &mut Vec<&'a str>,
+) {
+    let widths: Vec<usize> = tokens
+        .iter()
+        .map(|token| token.width(break_config.width_measure))
+        .collect();
+    let goal_width = break_config.goal_width(line_width);
+    let indentation_at = |start: usize| match (start, config.hanging_indentation) {
+        (1.., Some(hanging_indentation)) => hanging_indentation,
+        _ => config.indentation,
+    };
+
+    // `best[end]` is the minimum cost to break `tokens[..end]` with
+    // a line ending at `end`, and `starts[end]` is where that line starts.
+    let mut best = vec![0_u64; tokens.len() + 1];
+    let mut starts = vec![0; tokens.len() + 1];
+    for end in 1..=tokens.len() {
+        let penalty = match end < tokens.len() {
+            true if !break_config
+                .allows_break_after(tokens[end - 1].word, Some(tokens[end].word)) =>
+            {
+                NO_BREAK_PENALTY
+            }
+            true => break_penalty(
+                &break_config.priorities,
+                break_position(break_config, tokens, end),
+            ),
+            false => 0,
+        };
+        best[end] = u64::MAX;
+        let mut line_len = 0;
+        for start in (0..end).rev() {
+            line_len += widths[start];
+            if start + 1 < end {
+                line_len += tokens[start].space_after as usize;
+            }
+            // Lines are strictly shorter than `line_width`.
+            let max_line_len = line_width.saturating_sub(indentation_at(start) + 1);
+            let goal_line_len = goal_width.saturating_sub(indentation_at(start) + 1);
+            let line_cost = match (line_len <= max_line_len, end < tokens.len()) {
+                (true, true) => penalty + (goal_line_len.abs_diff(line_len) as u64).pow(2),
+                (true, false) => 0,
+                // Only a single token may overflow.
+                (false, _) if start + 1 == end => OVERFLOW_PENALTY,
+                (false, _) => break,
+            };
+            let cost = best[start].saturating_add(line_cost);
+            if cost < best[end] {
+                best[end] = cost;
+                starts[end] = start;
+            }
+        }
+    }
+    trace!(?best, ?starts);
+
+    let mut ends = Vec::new();
+    let mut end = tokens.len();
+    while end > 0 {
+        ends.push(end);
+        end = starts[end];
+    }
+    let mut start = 0;
+    for end in ends.into_iter().rev() {
+        push_line(
+            result,
+            indentation_at(start),
+            tokens[start..end].iter().copied(),
+        );
+        start = end;
+    }
+    // Lines of later segments hang, as with the greedy engine.
+    if let (1.., Some(hanging_indentation)) = (start, config.hanging_indentation) {
+        config.indentation = hanging_indentation;
+        config.hanging_indentation = None;
+    }
+}
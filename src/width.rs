@@ -0,0 +1,25 @@
+use unicode_width::UnicodeWidthStr;
+
+use super::*;
+
+/// How to measure the width of words against the line width limit.
+#[derive(clap::ValueEnum, Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WidthMeasure {
+    /// Count terminal display columns: East Asian Wide and Fullwidth
+    /// characters take two columns, and zero-width and combining characters
+    /// take none.
+    #[default]
+    Columns,
+    /// Count each `char` as one column.
+    Chars,
+}
+
+impl WidthMeasure {
+    pub fn width(self, text: &str) -> usize {
+        match self {
+            Self::Columns => text.width(),
+            Self::Chars => text.chars().count(),
+        }
+    }
+}
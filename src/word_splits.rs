@@ -0,0 +1,62 @@
+use super::*;
+
+/// Split `tokens` after internal hyphens if `break_config.split_hyphens`,
+/// and split tokens at least `max_width` wide after `/`, `_`, `.` and `-`
+/// if `break_config.split_long_words`.
+/// Glued tokens and others containing whitespace are kept whole.
+pub fn split_words<'a>(
+    break_config: &BreakConfig,
+    tokens: impl Iterator<Item = Token<'a>>,
+    max_width: usize,
+) -> Vec<Token<'a>> {
+    let mut result = Vec::new();
+    for token in tokens {
+        let long =
+            break_config.split_long_words && token.width(break_config.width_measure) >= max_width;
+        if !(break_config.split_hyphens || long)
+            || token.glued
+            || token.word.contains(|c: char| c.is_ascii_whitespace())
+        {
+            result.push(token);
+            continue;
+        }
+        let mut rest = token.word;
+        while let Some(end) = word_split_end(rest, break_config.split_hyphens, long) {
+            let (piece, after) = rest.split_at(end);
+            result.push(Token {
+                word: piece,
+                space_after: false,
+                line_break_after: false,
+                glued: false,
+            });
+            rest = after;
+        }
+        result.push(Token {
+            word: rest,
+            ..token
+        });
+    }
+    result
+}
+
+/// End of the first piece of `word` to split off, after an internal hyphen
+/// if `hyphens`, or after a path separator if `long`.
+fn word_split_end(word: &str, hyphens: bool, long: bool) -> Option<usize> {
+    let mut chars = word.char_indices().peekable();
+    let mut prev = None;
+    while let Some((index, char)) = chars.next() {
+        let next = chars.peek().map(|(_, next)| *next)?;
+        let is_hyphen =
+            char == '-' && prev.is_some_and(char::is_alphanumeric) && next.is_alphanumeric();
+        let is_path_separator = is_path_separator(char) && !is_path_separator(next);
+        if (hyphens && is_hyphen) || (long && is_path_separator && prev.is_some()) {
+            return Some(index + char.len_utf8());
+        }
+        prev = Some(char);
+    }
+    None
+}
+
+pub fn is_path_separator(char: char) -> bool {
+    matches!(char, '/' | '_' | '.' | '-')
+}
@@ -1,26 +1,89 @@
+use std::sync::OnceLock;
+
 use super::*;
 
-/// Whether a word ends with a split point.
+/// Whether a word ends with a split point, in English.
 /// Handles abbreviations using heuristics.
 pub fn word_sentence_position(word: &str) -> SentencePosition {
+    word_sentence_position_with(word, None, english())
+}
+
+/// Whether a word ends with a split point, in `language`.
+/// Handles abbreviations using `language` and heuristics,
+/// peeking at the `next` word to tell if an abbreviation ends a sentence.
+pub fn word_sentence_position_with(
+    word: &str,
+    next: Option<&str>,
+    language: &LanguagePack,
+) -> SentencePosition {
     use SentencePosition::*;
-    let mut chars = word.chars();
-    match chars.next_back() {
-        Some('.') if is_abbreviation(&mut chars) => {}
-        Some(last_char) if is_sentence_separator(last_char) => return End,
-        Some(last_char) if is_sub_sentence_separator(last_char) => return SubEnd,
+    // The innermost terminal punctuation decides, as in `end."` or `。」`.
+    let closed = word.trim_end_matches(|c| language.is_closing_punctuation(c));
+    let ended = match closed.chars().next_back() {
+        Some(last_char) if last_char == '.' || language.is_sentence_separator(last_char) => closed,
+        _ => word,
+    };
+    // A lone quote such as `"` closes rather than opens.
+    let unquoted = match ended.trim_start_matches(|c| language.is_sub_sentence_start(c)) {
+        "" => ended,
+        unquoted => unquoted,
+    };
+    let mut chars = unquoted.chars();
+    let last_char = chars.next_back();
+    // Known and guessed abbreviations only end sentences before capitals.
+    let abbreviation = last_char == Some('.')
+        && (language.abbreviations.contains(unquoted)
+            || !language.capitalized_nouns && is_abbreviation(&mut chars));
+    match last_char {
+        Some('.') if abbreviation && abbreviation_ends_sentence(unquoted, next, language) => {
+            return End
+        }
+        Some('.') if abbreviation => {}
+        Some(last_char) if language.is_sentence_separator(last_char) => return End,
+        Some(last_char) if language.is_sub_sentence_separator(last_char) => return SubEnd,
         _ => {}
     }
+    if ended.len() < word.len() {
+        // Closing punctuation after an abbreviation.
+        return SubEnd;
+    }
     match word.chars().next() {
-        Some(first_char) if is_sub_sentence_start(first_char) => return SubStart,
+        Some(first_char) if language.is_sub_sentence_start(first_char) => return SubStart,
         _ => {}
     };
-    match is_connection_word(word) {
-        true => ConnectionWord,
-        false => Other,
+    match language.connection_word_class(word) {
+        Some(_) => ConnectionWord,
+        None => Other,
     }
 }
 
+/// Whether abbreviation `word` ends a sentence because the `next`
+/// word starts with a capital letter,
+/// unless `word` is an initial, goes before names like "Dr." does,
+/// or `language` capitalizes nouns anyway.
+fn abbreviation_ends_sentence(word: &str, next: Option<&str>, language: &LanguagePack) -> bool {
+    let mut chars = word.chars();
+    let is_initial = matches!(
+        (chars.next(), chars.next(), chars.next()),
+        (Some(first_char), Some('.'), None) if first_char.is_uppercase()
+    );
+    if is_initial || language.capitalized_nouns || language.no_break_words.contains(word) {
+        return false;
+    }
+    next.and_then(|next| {
+        next.trim_start_matches(|c| language.is_sub_sentence_start(c))
+            .chars()
+            .next()
+    })
+    .is_some_and(char::is_uppercase)
+}
+
+/// The built-in English language pack.
+fn english() -> &'static LanguagePack {
+    static ENGLISH: OnceLock<LanguagePack> = OnceLock::new();
+    ENGLISH.get_or_init(|| Language::English.pack())
+}
+
 const MAX_ABBR_LEN: usize = 5;
 
 pub fn is_abbreviation(chars: &mut Chars) -> bool {
@@ -72,6 +135,36 @@ pub fn is_sub_sentence_separator(char: char) -> bool {
             | '”'
             | '\''
             | '’'
+            | '」'
+            | '』'
+            | '】'
+            | '〕'
+            | '〗'
+            | '〙'
+            | '〛'
+    )
+}
+
+/// Closing quotes and brackets.
+pub fn is_closing_punctuation(char: char) -> bool {
+    matches!(
+        char,
+        ')' | '）'
+            | ']'
+            | '］'
+            | '}'
+            | '｝'
+            | '"'
+            | '”'
+            | '\''
+            | '’'
+            | '」'
+            | '』'
+            | '】'
+            | '〕'
+            | '〗'
+            | '〙'
+            | '〛'
     )
 }
 
@@ -102,45 +195,51 @@ pub fn is_sub_sentence_start(char: char) -> bool {
     )
 }
 
+/// Whether `word` is an English connection word.
 pub fn is_connection_word(word: &str) -> bool {
-    matches!(
-        word,
-        "and"
-            | "or"
-            | "nor"
-            | "but"
-            | "except"
-            | "as"
-            | "since"
-            | "because"
-            | "so"
-            | "if"
-            | "then"
-            | "else"
-            | "than"
-            | "whether"
-            | "that"
-            | "which"
-            | "who"
-            | "where"
-            | "when"
-            | "while"
-            | "though"
-            | "although"
-            | "in"
-            | "on"
-            | "of"
-            | "by"
-            | "for"
-            | "from"
-            | "to"
-            | "through"
-            | "with"
-            | "via"
-    )
+    english().connection_word_class(word).is_some()
 }
 
+/// Kinds of connection words, which lines may break around differently.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ConnectionWordClass {
+    /// Coordinating conjunctions such as "and" and "but".
+    Conjunction,
+    /// Words that start a clause, such as "because" and "which".
+    Subordinator,
+    /// Prepositions such as "of" and "to".
+    Preposition,
+}
+
+/// Whether `word` is a bare number followed by a unit, as in "3 P.M.".
+pub fn is_number_before_unit(word: &str, next: Option<&str>) -> bool {
+    is_bare_number(word) && next.is_some_and(is_unit)
+}
+
+/// Whether `word` is a unit of measurement or time that goes after
+/// numbers, such as "cm" or "P.M.", ignoring punctuation after it.
+pub fn is_unit(word: &str) -> bool {
+    let unit = word.trim_end_matches([',', ';', ':', ')']);
+    UNITS.contains(&unit) || UNITS.contains(&unit.trim_end_matches('.'))
+}
+
+const UNITS: &[&str] = &[
+    "%", "°", "°C", "°F", "A.M.", "P.M.", "a.m.", "p.m.", "AM", "PM", "am", "pm", "nm", "mm", "cm",
+    "m", "km", "ft", "mi", "mg", "g", "kg", "lb", "lbs", "oz", "ml", "mL", "ms", "µs", "ns", "s",
+    "sec", "min", "hr", "hrs", "Hz", "kHz", "MHz", "GHz", "KB", "kB", "MB", "GB", "TB", "KiB",
+    "MiB", "GiB", "TiB", "mAh", "kW", "px", "pt",
+];
+
+/// Whether `word` is a number such as "3", "3.5" or "1,000"
+/// with no punctuation after it.
+pub fn is_bare_number(word: &str) -> bool {
+    word.ends_with(|c: char| c.is_ascii_digit())
+        && word
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == ',')
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub enum SentencePosition {
     /// Start of a sub-sentence.
     SubStart,
@@ -150,12 +249,21 @@ pub enum SentencePosition {
     SubEnd,
     /// Word to connect different parts of a sentence.
     ConnectionWord,
+    /// Hyphen between letters or digits inside a word,
+    /// split off by [`split_words`].
+    Hyphen,
+    /// `/`, `_`, `.` or any other `-` inside an overlong word,
+    /// split off by [`split_words`].
+    PathSeparator,
     /// Not a special sentence position.
+    #[default]
     Other,
 }
 
-impl Default for SentencePosition {
-    fn default() -> Self {
-        Self::Other
+impl SentencePosition {
+    /// Whether breaking here splits a word,
+    /// which ranks below other split points of the same priority.
+    pub fn is_inside_word(self) -> bool {
+        matches!(self, Self::Hyphen | Self::PathSeparator)
     }
 }